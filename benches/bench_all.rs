@@ -1,13 +1,13 @@
 use criterion::{criterion_main, criterion_group, Criterion};
-use curv::elliptic::curves::{Ed25519, Point};
+use curv::elliptic::curves::{Curve, Ed25519, Point, Scalar, Secp256k1, Secp256r1};
 
-criterion_group!(benches, ec_vrf, rka_vrf);
+criterion_group!(benches, ec_vrf, ec_vrf_eval_deterministic, ec_vrf_eval_random, rka_vrf, rka_vrf_secp256k1, rka_vrf_secp256r1, generator);
 criterion_main!(benches);
 
 fn ec_vrf(c: &mut Criterion) {
     let mut rng = rand::rngs::ThreadRng::default();
     let sk = curve25519_dalek::scalar::Scalar::random(&mut rng);
-    let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * &sk;
+    let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
     let x = curve25519_dalek::scalar::Scalar::random(&mut rng);
 
     c.bench_function(
@@ -21,31 +21,95 @@ fn ec_vrf(c: &mut Criterion) {
     c.bench_function(
         "EC-VRF verification",
         |b| b.iter(
-            || assert_eq!(true, output.verify(&vk, &x))
+            || assert!(output.verify(&vk, &x))
+        )
+    );
+}
+
+// The nonce is an ad-hoc `Sha512(sk || x)` reduced to a scalar, not the
+// RFC 9381 nonce derivation; it only stands in for the cost of hashing the
+// nonce, weighed against drawing it from `ThreadRng` below.
+fn ec_vrf_eval_deterministic(c: &mut Criterion) {
+    let mut rng = rand::rngs::ThreadRng::default();
+    let sk = curve25519_dalek::scalar::Scalar::random(&mut rng);
+    let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+    let x = curve25519_dalek::scalar::Scalar::random(&mut rng);
+
+    c.bench_function(
+        "EC-VRF evaluation, deterministic nonce",
+        |b| b.iter(|| {
+            let k = curve25519_dalek::scalar::Scalar::hash_from_bytes::<sha2::Sha512>(&[&sk.as_bytes()[..], x.as_bytes()].concat());
+            vrf::ec_vrf::VRFOutput::eval_with_nonce(&vk, &sk, &x, &k).unwrap()
+        })
+    );
+}
+
+fn ec_vrf_eval_random(c: &mut Criterion) {
+    let mut rng = rand::rngs::ThreadRng::default();
+    let sk = curve25519_dalek::scalar::Scalar::random(&mut rng);
+    let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+    let x = curve25519_dalek::scalar::Scalar::random(&mut rng);
+
+    c.bench_function(
+        "EC-VRF evaluation, random nonce",
+        |b| b.iter(
+            || vrf::ec_vrf::VRFOutput::eval_with_rng(&mut rng, &vk, &sk, &x)
         )
     );
 }
 
 fn rka_vrf(c: &mut Criterion) {
-    let sk = curv::elliptic::curves::Scalar::<Ed25519>::random();
-    let vk = Point::<Ed25519>::generator() * &sk;
-    let x = Point::<Ed25519>::generator() * &curv::elliptic::curves::Scalar::<Ed25519>::random();
+    bench_rka_vrf::<Ed25519>(c, "RKA-VRF");
+}
 
-    let g_tilde = Point::<Ed25519>::generator() * &curv::elliptic::curves::Scalar::<Ed25519>::random();
-    let h_tilde = Point::<Ed25519>::generator() * &curv::elliptic::curves::Scalar::<Ed25519>::random();
+fn rka_vrf_secp256k1(c: &mut Criterion) {
+    bench_rka_vrf::<Secp256k1>(c, "RKA-VRF secp256k1");
+}
+
+fn rka_vrf_secp256r1(c: &mut Criterion) {
+    bench_rka_vrf::<Secp256r1>(c, "RKA-VRF secp256r1");
+}
+
+fn bench_rka_vrf<C: Curve>(c: &mut Criterion, name: &str) {
+    let sk = Scalar::<C>::random();
+    let vk = Point::<C>::generator() * &sk;
+    let x = Point::<C>::generator() * &Scalar::<C>::random();
+
+    let g_tilde = Point::<C>::generator() * &Scalar::<C>::random();
+    let h_tilde = Point::<C>::generator() * &Scalar::<C>::random();
+
+    c.bench_function(
+        &format!("{} evaluation", name),
+        |b| b.iter(
+            || vrf::rka_vrf::VRFOutput::try_eval(&g_tilde, &h_tilde, &vk, &sk, &x).unwrap()
+        )
+    );
 
+    let output = vrf::rka_vrf::VRFOutput::try_eval(&g_tilde, &h_tilde, &vk, &sk, &x).unwrap();
     c.bench_function(
-        "RKA-VRF evaluation",
+        &format!("{} verification", name),
         |b| b.iter(
-            || vrf::rka_vrf::VRFOutput::eval(&g_tilde, &h_tilde, &vk, &sk, &x)
+            || assert!(output.verify(&g_tilde, &h_tilde, &vk, &x))
         )
     );
+}
+
+// Both look up curv's static generator on every call; only the first goes
+// through `Generator * Scalar`, which re-checks the product's order.
+fn generator(c: &mut Criterion) {
+    let s = Scalar::<Ed25519>::random();
 
-    let output = vrf::rka_vrf::VRFOutput::eval(&g_tilde, &h_tilde, &vk, &sk, &x);
     c.bench_function(
-        "RKA-VRF verification",
+        "Generator multiplication (order-checked)",
         |b| b.iter(
-            || assert_eq!(true, output.verify(&g_tilde, &h_tilde, &vk, &x))
+            || Point::<Ed25519>::generator() * &s
         )
     );
-}
\ No newline at end of file
+
+    c.bench_function(
+        "Generator multiplication (as_point)",
+        |b| b.iter(
+            || Point::<Ed25519>::generator().as_point() * &s
+        )
+    );
+}
@@ -0,0 +1,27 @@
+#![no_main]
+
+use curv::elliptic::curves::{Ed25519, Point, Scalar};
+use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, scalar::Scalar as DalekScalar};
+use libfuzzer_sys::fuzz_target;
+use vrf::{ec_vrf, rka_vrf::{hash_to_curve, Dst, Generators, InversionProof}};
+
+// Decoding must never panic, and whatever decodes must verify or be rejected
+// cleanly. The seeds in `corpus/proof_deserialization` are valid proofs for
+// the fixed statements below.
+fuzz_target!(|data: &[u8]| {
+    let g = Point::<Ed25519>::generator().to_point();
+    let h = hash_to_curve::<Ed25519>(&Dst::new(b"fuzz").unwrap(), b"h");
+    let params = Generators::<Ed25519>::from_seed(b"fuzz");
+    let gamma = Scalar::<Ed25519>::from(1234);
+    let (delta, theta) = (&g * &gamma, &h * &gamma.invert().unwrap());
+    if let Ok(proof) = InversionProof::<Ed25519>::from_bytes(data) {
+        proof.verify(&g, &h, &params.g_tilde, &params.h_tilde, &delta, &theta);
+    }
+
+    let vk = ED25519_BASEPOINT_POINT * DalekScalar::from(1234u64);
+    if let Ok(bytes) = <&[u8; 96]>::try_from(data) {
+        if let Ok(output) = ec_vrf::VRFOutput::from_proof_bytes(bytes) {
+            output.verify(&vk, &DalekScalar::from(1u64));
+        }
+    }
+});
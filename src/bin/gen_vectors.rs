@@ -0,0 +1,116 @@
+//! Prints reproducible test vectors for both schemes as JSON lines:
+//!
+//!     cargo run --bin gen_vectors [seed]
+//!
+//! Every value is hex. Keys, inputs and nonces are all derived from the seed,
+//! so the same seed always prints the same vectors.
+
+use curv::{arithmetic::Converter, elliptic::curves::{Ed25519, Point, Scalar}, BigInt};
+use curve25519_dalek::scalar::Scalar as DalekScalar;
+use sha2::{Digest, Sha512};
+use vrf::{ec_vrf, rka_vrf::{self, hash_to_curve, Dst, Generators, KeyPair}};
+
+const DEFAULT_SEED: &str = "rka-vrf test vectors";
+const RECORDS_PER_SCHEME: u64 = 4;
+
+fn derive(seed: &[u8], label: &[u8], i: u64) -> [u8; 64] {
+    Sha512::new().chain(seed).chain(label).chain(i.to_le_bytes()).finalize().as_slice().try_into().unwrap()
+}
+
+fn ec_vrf_record(seed: &[u8], i: u64) -> String {
+    let sk = ec_vrf::SecretKey::from_seed(derive(seed, b"ec-vrf sk", i)[..32].try_into().unwrap());
+    let vk = sk.public_key().as_point();
+    let x = DalekScalar::from_bytes_mod_order_wide(&derive(seed, b"ec-vrf x", i));
+    let k = DalekScalar::from_bytes_mod_order_wide(&derive(seed, b"ec-vrf nonce", i));
+    let output = ec_vrf::VRFOutput::eval_with_nonce(vk, sk.as_scalar(), &x, &k).unwrap();
+    format!(
+        r#"{{"scheme":"ec-vrf","sk":"{}","vk":"{}","x":"{}","proof":"{}","output":"{}"}}"#,
+        hex::encode(sk.as_scalar().as_bytes()),
+        hex::encode(sk.public_key().to_bytes()),
+        hex::encode(x.as_bytes()),
+        hex::encode(output.to_proof_bytes()),
+        hex::encode(output.verify_and_output(vk, &x).unwrap())
+    )
+}
+
+fn rka_vrf_record(seed: &[u8], i: u64) -> String {
+    let params = Generators::<Ed25519>::from_seed(seed);
+    let sk = Scalar::<Ed25519>::from_bigint(&BigInt::from_bytes(&derive(seed, b"rka-vrf sk", i)));
+    let keypair = KeyPair::from_secret(sk).unwrap();
+    let x: Point<Ed25519> = hash_to_curve(&Dst::new(b"rka-vrf x").unwrap(), &derive(seed, b"rka-vrf x", i));
+    let output = rka_vrf::VRFOutput::eval_deterministic(
+        &params.g_tilde,
+        &params.h_tilde,
+        keypair.public_key(),
+        keypair.secret_key(),
+        &x
+    ).unwrap();
+    format!(
+        r#"{{"scheme":"rka-vrf-ed25519","sk":"{}","vk":"{}","x":"{}","proof":"{}","output":"{}"}}"#,
+        hex::encode(&*keypair.secret_key().to_bytes()),
+        hex::encode(&*keypair.public_key().to_bytes(true)),
+        hex::encode(&*x.to_bytes(true)),
+        hex::encode(output.to_proof_bytes()),
+        hex::encode(output.output_value().to_bytes_array::<64>().unwrap())
+    )
+}
+
+fn records(seed: &[u8]) -> Vec<String> {
+    (0..RECORDS_PER_SCHEME)
+        .map(|i| ec_vrf_record(seed, i))
+        .chain((0..RECORDS_PER_SCHEME).map(|i| rka_vrf_record(seed, i)))
+        .collect()
+}
+
+fn main() {
+    let seed = std::env::args().nth(1).unwrap_or_else(|| DEFAULT_SEED.to_string());
+    for record in records(seed.as_bytes()) {
+        println!("{}", record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use curv::{arithmetic::Converter, elliptic::curves::{Ed25519, Point}, BigInt};
+    use curve25519_dalek::scalar::Scalar as DalekScalar;
+    use vrf::{ec_vrf, rka_vrf::{self, Generators}};
+
+    use super::{records, DEFAULT_SEED};
+
+    fn field<const N: usize>(record: &serde_json::Value, name: &str) -> [u8; N] {
+        hex::decode(record[name].as_str().unwrap()).unwrap().try_into().unwrap()
+    }
+
+    fn bytes(record: &serde_json::Value, name: &str) -> Vec<u8> {
+        hex::decode(record[name].as_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_records_reverify() {
+        let seed = DEFAULT_SEED.as_bytes();
+        let records = records(seed);
+        assert_eq!(records, super::records(seed));
+
+        let params = Generators::<Ed25519>::from_seed(seed);
+        for line in &records {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            match record["scheme"].as_str().unwrap() {
+                "ec-vrf" => {
+                    let vk = ec_vrf::PublicKey::from_bytes(&field(&record, "vk")).unwrap();
+                    let x = DalekScalar::from_canonical_bytes(field(&record, "x")).unwrap();
+                    let output = ec_vrf::VRFOutput::from_proof_bytes(&field(&record, "proof")).unwrap();
+                    assert_eq!(Some(field(&record, "output")), output.verify_and_output(vk.as_point(), &x));
+                }
+                "rka-vrf-ed25519" => {
+                    let vk = Point::<Ed25519>::from_bytes(&bytes(&record, "vk")).unwrap();
+                    let x = Point::<Ed25519>::from_bytes(&bytes(&record, "x")).unwrap();
+                    let proof = bytes(&record, "proof");
+                    assert_eq!(Ok(()), rka_vrf::VRFOutput::verify_canonical(&proof, &vk, &x, &params));
+                    let output = rka_vrf::VRFOutput::from_proof_bytes(&proof, &x).unwrap();
+                    assert_eq!(&BigInt::from_bytes(&bytes(&record, "output")), output.output_value());
+                }
+                scheme => panic!("unknown scheme {}", scheme)
+            }
+        }
+    }
+}
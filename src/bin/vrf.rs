@@ -0,0 +1,143 @@
+//! Evaluates and verifies either scheme from the shell, printing JSON:
+//!
+//!     vrf keygen [--scheme ec|rka]
+//!     vrf eval   [--scheme ec|rka] --sk <hex> --msg <hex>
+//!     vrf verify [--scheme ec|rka] --vk <hex> --msg <hex> --proof <hex>
+//!
+//! `--scheme` defaults to `ec`. The ec-vrf secret key is the 32-byte seed of
+//! `ec_vrf::SecretKey::from_seed`. rka-vrf runs on ed25519, with generators
+//! from `--seed <text>` (default `rka-vrf`). `verify` exits with 1 on an
+//! invalid proof, and any usage or decoding error exits with 2.
+
+use std::{collections::HashMap, process::exit};
+
+use curv::{arithmetic::Converter, elliptic::curves::{Ed25519, Point, Scalar}};
+use vrf::{
+    ec_vrf::{self, EcVrf},
+    error::VrfError,
+    rka_vrf::{self, Generators, KeyPair, VrfInput},
+    traits::Vrf
+};
+
+const USAGE: &str = "usage: vrf keygen|eval|verify [--scheme ec|rka] [--seed <text>] [--sk <hex>] [--vk <hex>] [--msg <hex>] [--proof <hex>]";
+const DEFAULT_SEED: &str = "rka-vrf";
+
+struct Args {
+    command: String,
+    flags: HashMap<String, String>
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let command = args.next().ok_or("missing command")?;
+        let mut flags = HashMap::new();
+        while let Some(flag) = args.next() {
+            let name = flag.strip_prefix("--").ok_or_else(|| format!("unexpected argument {}", flag))?;
+            let value = args.next().ok_or_else(|| format!("missing value for {}", flag))?;
+            flags.insert(name.to_string(), value);
+        }
+        Ok(Self { command, flags })
+    }
+
+    fn get(&self, name: &str) -> Result<&str, String> {
+        self.flags.get(name).map(String::as_str).ok_or_else(|| format!("missing --{}", name))
+    }
+
+    fn hex(&self, name: &str) -> Result<Vec<u8>, String> {
+        hex::decode(self.get(name)?).map_err(|_| format!("--{}: {}", name, VrfError::InvalidEncoding))
+    }
+}
+
+fn verdict(valid: bool) -> (String, bool) {
+    (format!(r#"{{"valid":{}}}"#, valid), valid)
+}
+
+fn invalid(name: &str, e: VrfError) -> String {
+    format!("--{}: {}", name, e)
+}
+
+fn ec(args: &Args) -> Result<(String, bool), String> {
+    match args.command.as_str() {
+        "keygen" => {
+            let seed: [u8; 32] = rand::random();
+            let sk = ec_vrf::SecretKey::from_seed(&seed);
+            Ok((format!(r#"{{"sk":"{}","vk":"{}"}}"#, hex::encode(seed), hex::encode(sk.public_key().to_bytes())), true))
+        }
+        "eval" => {
+            let seed: [u8; 32] = args.hex("sk")?.try_into().map_err(|_| invalid("sk", VrfError::InvalidLength))?;
+            let sk = ec_vrf::SecretKey::from_seed(&seed);
+            let (output, proof) = EcVrf.eval(sk.as_scalar(), &args.hex("msg")?);
+            Ok((format!(
+                r#"{{"vk":"{}","output":"{}","proof":"{}"}}"#,
+                hex::encode(sk.public_key().to_bytes()),
+                hex::encode(output),
+                proof.to_hex()
+            ), true))
+        }
+        "verify" => {
+            let vk: [u8; 32] = args.hex("vk")?.try_into().map_err(|_| invalid("vk", VrfError::InvalidLength))?;
+            let vk = ec_vrf::PublicKey::from_bytes(&vk).map_err(|e| invalid("vk", e))?;
+            let proof = ec_vrf::VRFOutput::from_hex(args.get("proof")?).map_err(|e| invalid("proof", e))?;
+            Ok(verdict(EcVrf.verify(vk.as_point(), &args.hex("msg")?, &proof)))
+        }
+        command => Err(format!("unknown command {}", command))
+    }
+}
+
+fn rka(args: &Args) -> Result<(String, bool), String> {
+    let params = Generators::<Ed25519>::from_seed(args.flags.get("seed").map_or(DEFAULT_SEED, String::as_str).as_bytes());
+    match args.command.as_str() {
+        "keygen" => {
+            let keypair = KeyPair::<Ed25519>::generate();
+            Ok((format!(
+                r#"{{"sk":"{}","vk":"{}"}}"#,
+                hex::encode(&*keypair.secret_key().to_bytes()),
+                hex::encode(&*keypair.public_key().to_bytes(true))
+            ), true))
+        }
+        "eval" => {
+            let sk = Scalar::<Ed25519>::from_bytes(&args.hex("sk")?).map_err(|_| invalid("sk", VrfError::NonCanonicalScalar))?;
+            let keypair = KeyPair::from_secret(sk).map_err(|e| invalid("sk", e))?;
+            let x: Point<Ed25519> = VrfInput::from_message(&args.hex("msg")?).into();
+            let output = rka_vrf::VRFOutput::try_eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x)
+                .map_err(|e| e.to_string())?;
+            Ok((format!(
+                r#"{{"vk":"{}","output":"{}","proof":"{}"}}"#,
+                hex::encode(&*keypair.public_key().to_bytes(true)),
+                hex::encode(output.output_value().to_bytes_array::<64>().unwrap()),
+                hex::encode(output.to_proof_bytes())
+            ), true))
+        }
+        "verify" => {
+            let vk = Point::<Ed25519>::from_bytes(&args.hex("vk")?).map_err(|_| invalid("vk", VrfError::InvalidPointEncoding))?;
+            let x: Point<Ed25519> = VrfInput::from_message(&args.hex("msg")?).into();
+            let proof = rka_vrf::VRFOutput::from_proof_bytes(&args.hex("proof")?, &x).map_err(|e| invalid("proof", e))?;
+            Ok(verdict(proof.verify(&params.g_tilde, &params.h_tilde, &vk, &x)))
+        }
+        command => Err(format!("unknown command {}", command))
+    }
+}
+
+fn run() -> Result<(String, bool), String> {
+    let args = Args::parse(std::env::args().skip(1))?;
+    match args.flags.get("scheme").map_or("ec", String::as_str) {
+        "ec" => ec(&args),
+        "rka" => rka(&args),
+        scheme => Err(format!("unknown scheme {}", scheme))
+    }
+}
+
+fn main() {
+    match run() {
+        Ok((json, success)) => {
+            println!("{}", json);
+            if !success {
+                exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("error: {}\n{}", e, USAGE);
+            exit(2);
+        }
+    }
+}
@@ -1,19 +1,216 @@
-use curve25519_dalek::{edwards::EdwardsPoint, scalar::Scalar, constants::ED25519_BASEPOINT_POINT};
+use curve25519_dalek::{edwards::{EdwardsPoint, EdwardsBasepointTable, CompressedEdwardsY}, scalar::Scalar, constants::ED25519_BASEPOINT_POINT, traits::IsIdentity};
+use rand::{rngs::OsRng, CryptoRng, RngCore};
 use sha2::{Sha512, Digest};
+use std::{fmt, num::NonZeroU64};
 
-pub struct VRFOutput {
+use crate::error::{decode_hex, VrfError};
+use crate::metrics::OpCounts;
+use crate::rka_vrf::ProofMetadata;
+use crate::traits::Vrf;
+use crate::transcript::TranscriptHasher;
+
+pub mod ristretto;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKey(EdwardsPoint);
+
+impl PublicKey {
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, VrfError> {
+        let point = decompress_point(bytes)?;
+        validate_public_key(&point)?;
+        Ok(Self(point))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.compress().to_bytes()
+    }
+
+    pub fn as_point(&self) -> &EdwardsPoint {
+        &self.0
+    }
+}
+
+impl TryFrom<&[u8; 32]> for PublicKey {
+    type Error = VrfError;
+
+    fn try_from(bytes: &[u8; 32]) -> Result<Self, VrfError> {
+        Self::from_bytes(bytes)
+    }
+}
+
+/// A secret key expanded from a 32-byte seed as in Ed25519 (RFC 8032 section
+/// 5.1.5): the low half of `Sha512(seed)` with the low three bits cleared,
+/// bit 255 cleared and bit 254 set.
+#[derive(Clone)]
+pub struct SecretKey {
+    scalar: Scalar,
+    public: PublicKey
+}
+
+impl SecretKey {
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        let mut bits = [0u8; 32];
+        bits.copy_from_slice(&Sha512::digest(seed)[..32]);
+        bits[0] &= 248;
+        bits[31] &= 127;
+        bits[31] |= 64;
+        let scalar = Scalar::from_bits(bits);
+        Self { scalar, public: PublicKey(ED25519_BASEPOINT_POINT * scalar) }
+    }
+
+    pub fn as_scalar(&self) -> &Scalar {
+        &self.scalar
+    }
+
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public
+    }
+}
+
+/// Compressed encodings of the eight points of order dividing 8, in the
+/// order of `curve25519_dalek::constants::EIGHT_TORSION`.
+pub const SMALL_ORDER_ENCODINGS: [[u8; 32]; 8] = [
+    // identity
+    [
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ],
+    // order 8
+    [
+        0xc7, 0x17, 0x6a, 0x70, 0x3d, 0x4d, 0xd8, 0x4f,
+        0xba, 0x3c, 0x0b, 0x76, 0x0d, 0x10, 0x67, 0x0f,
+        0x2a, 0x20, 0x53, 0xfa, 0x2c, 0x39, 0xcc, 0xc6,
+        0x4e, 0xc7, 0xfd, 0x77, 0x92, 0xac, 0x03, 0x7a,
+    ],
+    // order 4
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+    ],
+    // order 8
+    [
+        0x26, 0xe8, 0x95, 0x8f, 0xc2, 0xb2, 0x27, 0xb0,
+        0x45, 0xc3, 0xf4, 0x89, 0xf2, 0xef, 0x98, 0xf0,
+        0xd5, 0xdf, 0xac, 0x05, 0xd3, 0xc6, 0x33, 0x39,
+        0xb1, 0x38, 0x02, 0x88, 0x6d, 0x53, 0xfc, 0x05,
+    ],
+    // order 2
+    [
+        0xec, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f,
+    ],
+    // order 8
+    [
+        0x26, 0xe8, 0x95, 0x8f, 0xc2, 0xb2, 0x27, 0xb0,
+        0x45, 0xc3, 0xf4, 0x89, 0xf2, 0xef, 0x98, 0xf0,
+        0xd5, 0xdf, 0xac, 0x05, 0xd3, 0xc6, 0x33, 0x39,
+        0xb1, 0x38, 0x02, 0x88, 0x6d, 0x53, 0xfc, 0x85,
+    ],
+    // order 4
+    [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ],
+    // order 8
+    [
+        0xc7, 0x17, 0x6a, 0x70, 0x3d, 0x4d, 0xd8, 0x4f,
+        0xba, 0x3c, 0x0b, 0x76, 0x0d, 0x10, 0x67, 0x0f,
+        0x2a, 0x20, 0x53, 0xfa, 0x2c, 0x39, 0xcc, 0xc6,
+        0x4e, 0xc7, 0xfd, 0x77, 0x92, 0xac, 0x03, 0xfa,
+    ],
+];
+
+/// Whether `point` is one of the eight small-order points. Such a point is
+/// never a valid `vk`, `gamma` or `h`: it is killed by the cofactor and
+/// carries no information about the secret key.
+pub fn is_small_order(point: &EdwardsPoint) -> bool {
+    SMALL_ORDER_ENCODINGS.contains(point.compress().as_bytes())
+}
+
+/// Decodes a point in the RFC 8032 encoding every type in this module uses:
+/// `y` little-endian in the low 255 bits, and in the top bit of `bytes[31]`
+/// the sign of `x`, set iff `x` (reduced mod p) is odd. The two points
+/// sharing a `y` are negatives of each other, so an encoder using the
+/// opposite sign convention yields `-P`, which then fails to verify. Only
+/// canonical encodings are accepted: `y` must be below p and `x = 0` must
+/// have the sign bit clear, so every point has exactly one encoding.
+pub fn decompress_point(bytes: &[u8; 32]) -> Result<EdwardsPoint, VrfError> {
+    let point = CompressedEdwardsY(*bytes)
+        .decompress()
+        .ok_or(VrfError::InvalidPointEncoding)?;
+    if point.compress().as_bytes() != bytes {
+        return Err(VrfError::InvalidPointEncoding);
+    }
+    Ok(point)
+}
+
+fn validate_public_key(vk: &EdwardsPoint) -> Result<(), VrfError> {
+    if vk.is_identity() {
+        return Err(VrfError::IdentityPoint);
+    }
+    if is_small_order(vk) || !vk.is_torsion_free() {
+        return Err(VrfError::NotInSubgroup);
+    }
+    Ok(())
+}
+
+/// `CHALLENGE_LEN` is the width in bytes of the challenge `c`: the default
+/// full scalar, or 16 bytes as in RFC 9381, which keeps 128-bit soundness and
+/// shrinks the proof to 80 bytes. Prover and verifier must use the same width.
+/// Widths outside 16 to 32 bytes fail to build:
+///
+/// ```compile_fail
+/// use curve25519_dalek::{edwards::EdwardsPoint, scalar::Scalar};
+/// use vrf::ec_vrf::VRFOutput;
+/// let verify: fn(&VRFOutput<33>, &EdwardsPoint, &Scalar) -> bool = VRFOutput::<33>::verify;
+/// println!("{:p}", verify as *const ());
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct VRFOutput<const CHALLENGE_LEN: usize = 32> {
     gamma: EdwardsPoint,
     c: Scalar,
     s: Scalar,
     y: Vec<u8>
 }
 
-impl VRFOutput {
-    fn hash_point(x: &Scalar) -> EdwardsPoint {
-        EdwardsPoint::hash_from_bytes::<Sha512>(
-            &x.to_bytes()
-        )
+pub type ShortVRFOutput = VRFOutput<16>;
+
+impl <const N: usize> fmt::Debug for VRFOutput<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VRFOutput")
+            .field("gamma", &hex::encode(self.gamma.compress().as_bytes()))
+            .field("c", &hex::encode(self.c.as_bytes()))
+            .field("s", &hex::encode(self.s.as_bytes()))
+            .field("y", &hex::encode(&self.y))
+            .finish()
+    }
+}
+
+/// Formats as the lowercase hex of `to_proof_bytes`.
+impl fmt::Display for VRFOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// Formats as the lowercase hex of `to_proof_bytes`.
+impl fmt::Display for ShortVRFOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
     }
+}
+
+impl <const N: usize> VRFOutput<N> {
+    /// Length of `to_proof_bytes`: `gamma`, `c` truncated to `N` bytes, `s`.
+    pub const PROOF_LEN: usize = 32 + N + 32;
 
     fn hash_challenge(
         g: &EdwardsPoint,
@@ -23,70 +220,1404 @@ impl VRFOutput {
         gk: &EdwardsPoint,
         hk: &EdwardsPoint
     ) -> Scalar {
-        Scalar::hash_from_bytes::<Sha512>(&[
-            g.compress().to_bytes(),
-            h.compress().to_bytes(),
-            vk.compress().to_bytes(),
-            gamma.compress().to_bytes(),
-            gk.compress().to_bytes(),
-            hk.compress().to_bytes()
-        ].concat())
+        // Every proof is made and checked through here, so this bounds `N`
+        // for the whole type: a wider challenge does not fit in the digest
+        // prefix below, and a narrower one gives up 128-bit soundness.
+        const { assert!(16 <= N && N <= 32) };
+        let digest = [g, h, vk, gamma, gk, hk]
+            .iter()
+            .fold(TranscriptHasher::new(), |t, p| t.append(p.compress().as_bytes()))
+            .finalize();
+        if N == 32 {
+            return Scalar::from_bytes_mod_order_wide(&digest);
+        }
+        let mut c = [0u8; 32];
+        c[..N].copy_from_slice(&digest[..N]);
+        Scalar::from_bits(c)
     }
 
-    fn hash_output(gamma_f: &EdwardsPoint) -> Vec<u8> {
-        Sha512::digest(&gamma_f.compress().to_bytes()).to_vec()
+    fn eval_with_base<R: RngCore + CryptoRng>(rng: &mut R, vk: &EdwardsPoint, sk: &Scalar, h: &EdwardsPoint) -> Self {
+        Self::eval_with_base_and_nonce(vk, sk, h, &Scalar::random(rng))
     }
 
-    pub fn eval(vk: &EdwardsPoint, sk: &Scalar, x: &Scalar) -> Self {
-        let h = Self::hash_point(&x);
+    fn eval_with_base_and_nonce(vk: &EdwardsPoint, sk: &Scalar, h: &EdwardsPoint, k: &Scalar) -> Self {
         let gamma = h * sk;
-        let mut rng = rand::rngs::ThreadRng::default();
-        let k = Scalar::random(&mut rng);
         let gk = ED25519_BASEPOINT_POINT * k;
         let hk = h * k;
-        let c = Self::hash_challenge(&ED25519_BASEPOINT_POINT, &h, &vk, &gamma, &gk, &hk);
+        let c = Self::hash_challenge(&ED25519_BASEPOINT_POINT, h, vk, &gamma, &gk, &hk);
         let s = k - c * sk;
-        let y = Self::hash_output(&gamma.mul_by_cofactor());
+        let y = VRFOutput::hash_output(&gamma.mul_by_cofactor());
         Self { gamma, c, s, y }
     }
 
+    /// The output in the crate-wide 64-byte format shared with `rka_vrf`.
+    /// The two schemes are different functions, so their normalized outputs
+    /// are never expected to coincide, even on related inputs.
+    pub fn normalize_output(&self) -> [u8; 64] {
+        crate::output::normalize(self.y.as_slice().try_into().unwrap())
+    }
+
+    /// `n` independent values derived from `gamma`, e.g. for sortition. Only
+    /// meaningful once `verify` has succeeded.
+    pub fn expand(&self, n: usize) -> Vec<[u8; 64]> {
+        crate::output::expand(self.gamma.mul_by_cofactor().compress().as_bytes(), n)
+    }
+
+    /// First 8 bytes of the output, little-endian.
+    pub fn output_u64(&self) -> u64 {
+        u64::from_le_bytes(self.y[..8].try_into().unwrap())
+    }
+
+    /// Uniform value in `0..n`. Draws 8-byte words from the output, rejecting
+    /// those above the largest multiple of `n` to avoid modulo bias, and
+    /// rehashes the output with a counter in the unlikely case all are rejected.
+    /// Panics if `n` is zero; not available with the `no_panic` feature.
+    #[cfg(not(feature = "no_panic"))]
+    pub fn output_in_range(&self, n: u64) -> u64 {
+        self.output_in_nonzero_range(NonZeroU64::new(n).expect("range must be non-empty"))
+    }
+
+    /// `output_in_range` with the non-empty range enforced by the type.
+    pub fn output_in_nonzero_range(&self, n: NonZeroU64) -> u64 {
+        let n = n.get();
+        let zone = u64::MAX - u64::MAX % n;
+        let mut block = self.y.clone();
+        let mut ctr = 0u32;
+        loop {
+            for word in block.chunks_exact(8) {
+                let v = u64::from_le_bytes(word.try_into().unwrap());
+                if v < zone {
+                    return v % n;
+                }
+            }
+            ctr += 1;
+            block = Sha512::new().chain(&self.y).chain(ctr.to_le_bytes()).finalize().to_vec();
+        }
+    }
+
     pub fn verify(&self, vk: &EdwardsPoint, x: &Scalar) -> bool {
+        self.verify_with_base(vk, &VRFOutput::hash_point(x))
+    }
+
+    /// `verify` with `h = VRFOutput::hash_point(x)` computed by the caller,
+    /// e.g. once for many proofs on the same input. The result says nothing
+    /// about `x` unless `h` really is its hash.
+    pub fn verify_with_base(&self, vk: &EdwardsPoint, h: &EdwardsPoint) -> bool {
+        verify_with_base::<N>(&self.gamma, &self.c, &self.s, &self.y, vk, h, &mut OpCounts::default())
+    }
+
+    pub fn verify_with_context(&self, vk: &EdwardsPoint, x: &Scalar, context: &[u8]) -> bool {
+        let h = VRFOutput::hash_point_with_context(x, context);
+        verify_with_base::<N>(&self.gamma, &self.c, &self.s, &self.y, vk, &h, &mut OpCounts::default())
+    }
+
+    pub fn verify_with_session(&self, vk: &EdwardsPoint, x: &Scalar, session_id: &[u8]) -> bool {
+        self.verify_with_base(vk, &VRFOutput::hash_point_with_session(x, session_id))
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn verify_with_metrics(&self, vk: &EdwardsPoint, x: &Scalar) -> (bool, OpCounts) {
+        let mut ops = OpCounts { hashes: 1, ..OpCounts::default() };
+        let h = VRFOutput::hash_point(x);
+        let valid = verify_with_base::<N>(&self.gamma, &self.c, &self.s, &self.y, vk, &h, &mut ops);
+        (valid, ops)
+    }
+
+    /// Checks only the proof that `gamma = h * sk`, not that `y` is the hash
+    /// of `gamma`. This does NOT bind the output: a valid proof passes with
+    /// any `y`, so use it only when `y` is trusted from elsewhere.
+    pub fn verify_proof_only(&self, vk: &EdwardsPoint, x: &Scalar) -> bool {
+        verify_proof::<N>(&self.gamma, &self.c, &self.s, vk, &VRFOutput::hash_point(x), &mut OpCounts::default())
+    }
+
+    /// Verifies the proof and only then derives the output from `gamma`, as
+    /// `ECVRF_verify` in RFC 9381 returns beta.
+    pub fn verify_and_output(&self, vk: &EdwardsPoint, x: &Scalar) -> Option<[u8; 64]> {
+        let h = VRFOutput::hash_point(x);
+        if !verify_proof::<N>(&self.gamma, &self.c, &self.s, vk, &h, &mut OpCounts::default()) {
+            return None;
+        }
+        VRFOutput::hash_output(&self.gamma.mul_by_cofactor()).try_into().ok()
+    }
+}
+
+impl VRFOutput {
+    /// The base point `h` that `x` is evaluated on, as `verify_with_base`
+    /// takes it. Every `x` is a valid input, the zero scalar included; like
+    /// the empty message for `eval_message`, it is not special-cased anywhere.
+    // `hash_from_bytes` multiplies the Elligator output by the cofactor, so `h`
+    // (and therefore `gamma = h * sk`) always lies in the prime-order subgroup.
+    pub fn hash_point(x: &Scalar) -> EdwardsPoint {
+        Self::hash_point_with_context(x, &[])
+    }
+
+    // `x` has a fixed width, so appending the context keeps the encoding
+    // unambiguous, and an empty context hashes exactly as before.
+    fn hash_point_with_context(x: &Scalar, context: &[u8]) -> EdwardsPoint {
+        Self::hash_bytes_to_point(&[&x.to_bytes(), context].concat())
+    }
+
+    fn hash_point_with_session(x: &Scalar, session_id: &[u8]) -> EdwardsPoint {
+        Self::hash_bytes_to_point(&session_bytes(x, session_id))
+    }
+
+    fn hash_bytes_to_point(bytes: &[u8]) -> EdwardsPoint {
+        Self::hash_bytes_to_point_with(bytes, EdwardsPoint::hash_from_bytes::<Sha512>)
+    }
+
+    // If `h` collides with the base point the two statements of the Schnorr
+    // proof collapse into one, and if it is the identity (or, from a broken
+    // map, any small-order point) `gamma` carries nothing about `sk`. Either
+    // way, re-derive `h` with an appended counter; `check_proof` rejects a
+    // small-order `h` regardless.
+    fn hash_bytes_to_point_with(bytes: &[u8], map: impl Fn(&[u8]) -> EdwardsPoint) -> EdwardsPoint {
+        let mut h = map(bytes);
+        let mut ctr = 0u32;
+        while h == ED25519_BASEPOINT_POINT || is_small_order(&h) {
+            ctr += 1;
+            h = map(&[bytes, &ctr.to_le_bytes()].concat());
+        }
+        h
+    }
+
+    fn hash_output(gamma_f: &EdwardsPoint) -> Vec<u8> {
+        Sha512::digest(&gamma_f.compress().to_bytes()).to_vec()
+    }
+
+    pub fn eval(vk: &EdwardsPoint, sk: &Scalar, x: &Scalar) -> Self {
+        Self::eval_with_rng(&mut OsRng, vk, sk, x)
+    }
+
+    /// `eval` with the nonce drawn from `rng` rather than the OS.
+    pub fn eval_with_rng<R: RngCore + CryptoRng>(rng: &mut R, vk: &EdwardsPoint, sk: &Scalar, x: &Scalar) -> Self {
+        Self::eval_with_base(rng, vk, sk, &Self::hash_point(x))
+    }
+
+    /// Evaluates under a context string, so independent protocol instances
+    /// sharing a key get unrelated outputs for the same input. Verify with
+    /// `verify_with_context` and the same context.
+    pub fn eval_with_context(vk: &EdwardsPoint, sk: &Scalar, x: &Scalar, context: &[u8]) -> Self {
+        Self::eval_with_context_with_rng(&mut OsRng, vk, sk, x, context)
+    }
+
+    pub fn eval_with_context_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        vk: &EdwardsPoint,
+        sk: &Scalar,
+        x: &Scalar,
+        context: &[u8]
+    ) -> Self {
+        Self::eval_with_base(rng, vk, sk, &Self::hash_point_with_context(x, context))
+    }
+
+    /// Evaluates for one session, so repeated evaluations on `x` differ per
+    /// session while each verifies under `verify_with_session` with its
+    /// session id. Unlike a context, which names a long-lived protocol
+    /// instance, the session id is ephemeral and sent along with the proof.
+    pub fn eval_with_session(vk: &EdwardsPoint, sk: &Scalar, x: &Scalar, session_id: &[u8]) -> Self {
+        Self::eval_with_session_with_rng(&mut OsRng, vk, sk, x, session_id)
+    }
+
+    pub fn eval_with_session_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        vk: &EdwardsPoint,
+        sk: &Scalar,
+        x: &Scalar,
+        session_id: &[u8]
+    ) -> Self {
+        Self::eval_with_base(rng, vk, sk, &Self::hash_point_with_session(x, session_id))
+    }
+
+    pub fn eval_message(vk: &EdwardsPoint, sk: &Scalar, msg: &[u8]) -> Self {
+        VrfSigner::new(vk, sk).update(msg).finalize()
+    }
+
+    /// Evaluates with a caller-supplied nonce, e.g. one generated jointly in
+    /// an MPC protocol.
+    ///
+    /// **Never reuse `k`.** Two proofs sharing a nonce on different inputs
+    /// reveal `sk = (s1 - s2) / (c2 - c1)`.
+    pub fn eval_with_nonce(vk: &EdwardsPoint, sk: &Scalar, x: &Scalar, k: &Scalar) -> Result<Self, VrfError> {
+        if *k == Scalar::zero() {
+            return Err(VrfError::ZeroNonce);
+        }
+        Ok(Self::eval_with_base_and_nonce(vk, sk, &Self::hash_point(x), k))
+    }
+
+    /// Evaluates with the nonce `k = k0 + H(B * k0 || commitment)`, which
+    /// binds `commitment` into the proof without changing its format
+    /// (sign-to-contract). Returns the proof and `B * k0`, the opening that
+    /// `verify_commitment` checks against the commitment.
+    pub fn eval_committed(vk: &EdwardsPoint, sk: &Scalar, x: &Scalar, commitment: &[u8]) -> (Self, EdwardsPoint) {
+        Self::eval_committed_with_rng(&mut OsRng, vk, sk, x, commitment)
+    }
+
+    pub fn eval_committed_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        vk: &EdwardsPoint,
+        sk: &Scalar,
+        x: &Scalar,
+        commitment: &[u8]
+    ) -> (Self, EdwardsPoint) {
+        let k0 = Scalar::random(rng);
+        let opening = ED25519_BASEPOINT_POINT * k0;
+        let k = k0 + Self::commitment_tweak(&opening, commitment);
+        (Self::eval_with_base_and_nonce(vk, sk, &Self::hash_point(x), &k), opening)
+    }
+
+    /// Verifies the proof, then that its nonce commitment `B * k` (recovered
+    /// as `vk * c + B * s`) is `opening + B * H(opening || commitment)`.
+    pub fn verify_commitment(&self, vk: &EdwardsPoint, x: &Scalar, opening: &EdwardsPoint, commitment: &[u8]) -> bool {
+        if !self.verify(vk, x) {
+            return false;
+        }
         let u = vk * self.c + ED25519_BASEPOINT_POINT * self.s;
-        let h = Self::hash_point(&x);
-        // Trait for checking whether a point is on the curve.
-        //
-        // This trait is only for debugging/testing, since it should be
-        // impossible for a `curve25519-dalek` user to construct an invalid
-        // point.
-        // pub(crate) trait ValidityCheck {
-        //     /// Checks whether the point is on the curve. Not CT.
-        //     fn is_valid(&self) -> bool;
-        // }
-        // if !self.gamma.is_valid() {
-        //     return false;
-        // }
-        let v = self.gamma * self.c + h * self.s;
-        let c_comp = Self::hash_challenge(&ED25519_BASEPOINT_POINT, &h, vk, &self.gamma, &u, &v);
-        let y_comp = Self::hash_output(&self.gamma.mul_by_cofactor());
-        self.c == c_comp && self.y == y_comp
+        u == opening + ED25519_BASEPOINT_POINT * Self::commitment_tweak(opening, commitment)
+    }
+
+    fn commitment_tweak(opening: &EdwardsPoint, commitment: &[u8]) -> Scalar {
+        let digest = TranscriptHasher::new()
+            .append(b"ec-vrf-commitment")
+            .append(opening.compress().as_bytes())
+            .append(commitment)
+            .finalize();
+        Scalar::from_bytes_mod_order_wide(&digest)
+    }
+
+    pub fn to_proof_bytes(&self) -> [u8; 96] {
+        let mut bytes = [0u8; 96];
+        bytes[..32].copy_from_slice(self.gamma.compress().as_bytes());
+        bytes[32..64].copy_from_slice(self.c.as_bytes());
+        bytes[64..].copy_from_slice(self.s.as_bytes());
+        bytes
+    }
+
+    pub fn from_proof_bytes(bytes: &[u8; 96]) -> Result<Self, VrfError> {
+        let gamma = decompress_point(bytes[..32].try_into().unwrap())?;
+        if is_small_order(&gamma) || !gamma.is_torsion_free() {
+            return Err(VrfError::NotInSubgroup);
+        }
+        // Scalars must be reduced mod l, otherwise `s + l` would be a second
+        // valid encoding of the same proof.
+        let c = Scalar::from_canonical_bytes(bytes[32..64].try_into().unwrap())
+            .ok_or(VrfError::NonCanonicalScalar)?;
+        let s = Scalar::from_canonical_bytes(bytes[64..].try_into().unwrap())
+            .ok_or(VrfError::NonCanonicalScalar)?;
+        let y = Self::hash_output(&gamma.mul_by_cofactor());
+        Ok(Self { gamma, c, s, y })
+    }
+
+    /// `from_proof_bytes` for a buffer of unchecked length.
+    pub fn from_proof_slice(bytes: &[u8]) -> Result<Self, VrfError> {
+        Self::from_proof_bytes(bytes.try_into().map_err(|_| VrfError::InvalidLength)?)
+    }
+
+    /// Lowercase hex of `to_proof_bytes`.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_proof_bytes())
+    }
+
+    pub fn from_hex(s: &str) -> Result<Self, VrfError> {
+        Self::from_proof_slice(&decode_hex(s)?)
+    }
+
+    pub fn fingerprint(&self) -> String {
+        hex::encode(&Sha512::digest(&self.to_proof_bytes())[..8])
+    }
+
+    pub fn verify_with_metadata(&self, vk: &EdwardsPoint, x: &Scalar) -> Result<ProofMetadata, VrfError> {
+        if !self.verify(vk, x) {
+            return Err(VrfError::ProofRejected);
+        }
+        Ok(ProofMetadata {
+            curve: "ed25519",
+            suite: "ec-vrf",
+            output_len: self.y.len(),
+            proof_len: self.to_proof_bytes().len()
+        })
+    }
+
+    pub fn verify_message(&self, vk: &EdwardsPoint, msg: &[u8]) -> bool {
+        VrfVerifier::new(vk, self).update(msg).finalize()
+    }
+
+    /// Index of the first of `candidates` this output verifies for as a
+    /// message, e.g. to find which of several tickets a proof is for.
+    pub fn verify_any(&self, vk: &EdwardsPoint, candidates: &[&[u8]]) -> Option<usize> {
+        candidates.iter().position(|msg| self.verify_message(vk, msg))
+    }
+}
+
+// The tag, the input and the session id, each framed by `TranscriptHasher`.
+// The tag runs past byte 31, which is then ASCII and above the top byte of
+// any canonical scalar, so no `x || context` can start the same way.
+fn session_bytes(x: &Scalar, session_id: &[u8]) -> Vec<u8> {
+    TranscriptHasher::with_sink(Vec::new())
+        .append(b"ec-vrf-session-hash-to-point")
+        .append(x.as_bytes())
+        .append(session_id)
+        .into_inner()
+}
+
+impl ShortVRFOutput {
+    pub fn eval_short(vk: &EdwardsPoint, sk: &Scalar, x: &Scalar) -> Self {
+        Self::eval_short_with_rng(&mut OsRng, vk, sk, x)
+    }
+
+    pub fn eval_short_with_rng<R: RngCore + CryptoRng>(rng: &mut R, vk: &EdwardsPoint, sk: &Scalar, x: &Scalar) -> Self {
+        Self::eval_with_base(rng, vk, sk, &VRFOutput::hash_point(x))
+    }
+
+    pub fn to_proof_bytes(&self) -> [u8; 80] {
+        let mut bytes = [0u8; 80];
+        bytes[..32].copy_from_slice(self.gamma.compress().as_bytes());
+        bytes[32..48].copy_from_slice(&self.c.as_bytes()[..16]);
+        bytes[48..].copy_from_slice(self.s.as_bytes());
+        bytes
+    }
+
+    pub fn from_short_proof_bytes(bytes: &[u8; 80]) -> Result<Self, VrfError> {
+        let gamma = decompress_point(bytes[..32].try_into().unwrap())?;
+        if is_small_order(&gamma) || !gamma.is_torsion_free() {
+            return Err(VrfError::NotInSubgroup);
+        }
+        let mut c = [0u8; 32];
+        c[..16].copy_from_slice(&bytes[32..48]);
+        let s = Scalar::from_canonical_bytes(bytes[48..].try_into().unwrap())
+            .ok_or(VrfError::NonCanonicalScalar)?;
+        let y = VRFOutput::hash_output(&gamma.mul_by_cofactor());
+        Ok(Self { gamma, c: Scalar::from_bits(c), s, y })
+    }
+
+    pub fn from_short_proof_slice(bytes: &[u8]) -> Result<Self, VrfError> {
+        Self::from_short_proof_bytes(bytes.try_into().map_err(|_| VrfError::InvalidLength)?)
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_proof_bytes())
+    }
+
+    pub fn from_short_hex(s: &str) -> Result<Self, VrfError> {
+        Self::from_short_proof_slice(&decode_hex(s)?)
     }
 }
 
+#[cfg(feature = "serde")]
+const SERIALIZED_LEN: usize = VRFOutput::<32>::PROOF_LEN + 64;
+
+/// Serialized as a fixed 160-byte array: compressed `gamma`, canonical `c` and
+/// `s`, then the 64-byte output `y`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for VRFOutput {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeTuple;
+        let mut tuple = serializer.serialize_tuple(SERIALIZED_LEN)?;
+        for byte in self.to_proof_bytes().iter().chain(&self.y) {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for VRFOutput {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+            type Value = VRFOutput;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{} bytes of an ec_vrf proof and output", SERIALIZED_LEN)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<VRFOutput, A::Error> {
+                use serde::de::Error;
+                let mut bytes = [0u8; SERIALIZED_LEN];
+                for (i, byte) in bytes.iter_mut().enumerate() {
+                    *byte = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(i, &self))?;
+                }
+                let output = VRFOutput::from_proof_bytes(bytes[..96].try_into().unwrap())
+                    .map_err(A::Error::custom)?;
+                if output.y != bytes[96..] {
+                    return Err(A::Error::custom("output does not match gamma"));
+                }
+                Ok(output)
+            }
+        }
+
+        deserializer.deserialize_tuple(SERIALIZED_LEN, BytesVisitor)
+    }
+}
+
+/// The hash-to-curve `eval` and `verify` map their input through:
+/// `EdwardsPoint::hash_from_bytes::<Sha512>` (Elligator 2, cofactor cleared),
+/// re-derived with a little-endian `u32` counter appended in the negligible
+/// case that it hits the base point or a small-order point. `eval` hashes `x.to_bytes()` (followed by
+/// the context, if any); `eval_message` hashes `Sha512(msg)`.
+pub fn hash_to_curve(x: &[u8]) -> EdwardsPoint {
+    VRFOutput::hash_bytes_to_point(x)
+}
+
+pub struct EcVrf;
+
+impl Vrf for EcVrf {
+    type PublicKey = EdwardsPoint;
+    type SecretKey = Scalar;
+    type Output = Vec<u8>;
+    type Proof = VRFOutput;
+
+    fn keygen(&self) -> (Scalar, EdwardsPoint) {
+        let sk = Scalar::random(&mut OsRng);
+        (sk, ED25519_BASEPOINT_POINT * sk)
+    }
+
+    fn eval(&self, sk: &Scalar, msg: &[u8]) -> (Vec<u8>, VRFOutput) {
+        let output = VRFOutput::eval_message(&(ED25519_BASEPOINT_POINT * sk), sk, msg);
+        (output.y.clone(), output)
+    }
+
+    fn verify(&self, vk: &EdwardsPoint, msg: &[u8], proof: &VRFOutput) -> bool {
+        proof.verify_message(vk, msg)
+    }
+}
+
+/// A validated verification key with a precomputed multiplication table, for
+/// verifiers that check many proofs against the same key.
+#[derive(Clone)]
+pub struct VerificationKey {
+    point: EdwardsPoint,
+    table: EdwardsBasepointTable
+}
+
+impl VerificationKey {
+    pub fn new(vk: &EdwardsPoint) -> Result<Self, VrfError> {
+        validate_public_key(vk)?;
+        Ok(Self { point: *vk, table: EdwardsBasepointTable::create(vk) })
+    }
+
+    pub fn as_point(&self) -> &EdwardsPoint {
+        &self.point
+    }
+
+    /// Same as `proof.verify_message(vk, msg)`.
+    pub fn verify<const N: usize>(&self, msg: &[u8], proof: &VRFOutput<N>) -> bool {
+        let h = VRFOutput::hash_bytes_to_point(&Sha512::digest(msg));
+        let ops = &mut OpCounts::default();
+        check_proof::<N>(&proof.gamma, &proof.c, &proof.s, &self.point, &(&self.table * &proof.c), &h, ops)
+            && proof.y == VRFOutput::hash_output(&proof.gamma.mul_by_cofactor())
+    }
+}
+
+impl From<PublicKey> for VerificationKey {
+    fn from(vk: PublicKey) -> Self {
+        Self { point: vk.0, table: EdwardsBasepointTable::create(&vk.0) }
+    }
+}
+
+/// Incremental counterpart of `VRFOutput::eval_message`. Chunks are absorbed
+/// into a Sha512 whose digest is then mapped to the curve, so the message never
+/// needs to be held in memory at once.
+pub struct VrfSigner {
+    vk: EdwardsPoint,
+    sk: Scalar,
+    hasher: Sha512
+}
+
+impl VrfSigner {
+    pub fn new(vk: &EdwardsPoint, sk: &Scalar) -> Self {
+        Self { vk: *vk, sk: *sk, hasher: Sha512::new() }
+    }
+
+    pub fn update(mut self, chunk: &[u8]) -> Self {
+        self.hasher.update(chunk);
+        self
+    }
+
+    pub fn finalize(self) -> VRFOutput {
+        self.finalize_with_rng(&mut OsRng)
+    }
+
+    pub fn finalize_with_rng<R: RngCore + CryptoRng>(self, rng: &mut R) -> VRFOutput {
+        let h = VRFOutput::hash_bytes_to_point(&self.hasher.finalize());
+        VRFOutput::eval_with_base(rng, &self.vk, &self.sk, &h)
+    }
+}
+
+/// Incremental counterpart of `VRFOutput::verify_message`.
+pub struct VrfVerifier<'a> {
+    vk: EdwardsPoint,
+    output: &'a VRFOutput,
+    hasher: Sha512
+}
+
+impl <'a> VrfVerifier<'a> {
+    pub fn new(vk: &EdwardsPoint, output: &'a VRFOutput) -> Self {
+        Self { vk: *vk, output, hasher: Sha512::new() }
+    }
+
+    pub fn update(mut self, chunk: &[u8]) -> Self {
+        self.hasher.update(chunk);
+        self
+    }
+
+    pub fn finalize(self) -> bool {
+        let h = VRFOutput::hash_bytes_to_point(&self.hasher.finalize());
+        let output = self.output;
+        verify_with_base::<32>(&output.gamma, &output.c, &output.s, &output.y, &self.vk, &h, &mut OpCounts::default())
+    }
+}
+
+/// Indices `(i, j)`, `i < j`, of the first two outputs sharing `gamma`,
+/// ordered by `j`: the same key evaluated twice on one input.
+pub fn find_duplicate_gammas<const N: usize>(outputs: &[VRFOutput<N>]) -> Option<(usize, usize)> {
+    let mut seen = std::collections::HashMap::new();
+    for (j, output) in outputs.iter().enumerate() {
+        if let Some(i) = seen.insert(output.gamma.compress().to_bytes(), j) {
+            return Some((i, j));
+        }
+    }
+    None
+}
+
+pub fn verify_split(
+    gamma: &EdwardsPoint,
+    c: &Scalar,
+    s: &Scalar,
+    y: &[u8],
+    vk: &EdwardsPoint,
+    x: &Scalar
+) -> bool {
+    verify_with_base::<32>(gamma, c, s, y, vk, &VRFOutput::hash_point(x), &mut OpCounts::default())
+}
+
+fn verify_with_base<const N: usize>(
+    gamma: &EdwardsPoint,
+    c: &Scalar,
+    s: &Scalar,
+    y: &[u8],
+    vk: &EdwardsPoint,
+    h: &EdwardsPoint,
+    ops: &mut OpCounts
+) -> bool {
+    if !verify_proof::<N>(gamma, c, s, vk, h, ops) {
+        return false;
+    }
+    ops.hashes += 1;
+    y == VRFOutput::hash_output(&gamma.mul_by_cofactor()).as_slice()
+}
+
+fn verify_proof<const N: usize>(
+    gamma: &EdwardsPoint,
+    c: &Scalar,
+    s: &Scalar,
+    vk: &EdwardsPoint,
+    h: &EdwardsPoint,
+    ops: &mut OpCounts
+) -> bool {
+    ops.point_muls += 1;
+    if validate_public_key(vk).is_err() {
+        return false;
+    }
+    check_proof::<N>(gamma, c, s, vk, &(vk * c), h, ops)
+}
+
+// `vk_c` is `vk * c`, left to the caller so `VerificationKey` can take it from
+// its table. `vk` must already be validated.
+fn check_proof<const N: usize>(
+    gamma: &EdwardsPoint,
+    c: &Scalar,
+    s: &Scalar,
+    vk: &EdwardsPoint,
+    vk_c: &EdwardsPoint,
+    h: &EdwardsPoint,
+    ops: &mut OpCounts
+) -> bool {
+    ops.point_muls += 1;
+    // `hash_output` clears the cofactor, so `gamma` and `gamma + T` for any
+    // torsion point `T` share an output; only the torsion-free one is valid.
+    if is_small_order(gamma) || is_small_order(h) || !gamma.is_torsion_free() {
+        return false;
+    }
+    let u = vk_c + ED25519_BASEPOINT_POINT * s;
+    // Trait for checking whether a point is on the curve.
+    //
+    // This trait is only for debugging/testing, since it should be
+    // impossible for a `curve25519-dalek` user to construct an invalid
+    // point.
+    // pub(crate) trait ValidityCheck {
+    //     /// Checks whether the point is on the curve. Not CT.
+    //     fn is_valid(&self) -> bool;
+    // }
+    // if !gamma.is_valid() {
+    //     return false;
+    // }
+    let v = gamma * c + h * s;
+    ops.point_muls += 4;
+    ops.point_adds += 2;
+    ops.hashes += 1;
+    *c == VRFOutput::<N>::hash_challenge(&ED25519_BASEPOINT_POINT, h, vk, gamma, &u, &v)
+}
+
 #[cfg(test)]
 mod tests {
     // use std::time::{SystemTime, Duration};
 
     use curve25519_dalek::scalar::Scalar;
-    use super::VRFOutput;
+    use rand::Rng;
+    use crate::error::VrfError;
+    use curve25519_dalek::{edwards::EdwardsPoint, traits::Identity};
+    use crate::traits::Vrf;
+    use super::{decompress_point, EcVrf, hash_to_curve, is_small_order, SMALL_ORDER_ENCODINGS, PublicKey, SecretKey, VerificationKey, VRFOutput, ShortVRFOutput, VrfSigner, VrfVerifier, find_duplicate_gammas, verify_split};
 
     #[test]
     fn test_valid() {
         let mut rng = rand::rngs::ThreadRng::default();
         let sk = Scalar::random(&mut rng);
-        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * &sk;
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
         let x = Scalar::random(&mut rng);
         let output = VRFOutput::eval(&vk, &sk, &x);
-        assert_eq!(true, output.verify(&vk, &x))
+        assert!(output.verify(&vk, &x))
+    }
+
+    #[test]
+    fn test_public_key_from_bytes() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let public_key = PublicKey::from_bytes(&vk.compress().to_bytes()).unwrap();
+        assert_eq!(vk, *public_key.as_point());
+        assert_eq!(Ok(public_key), PublicKey::try_from(&public_key.to_bytes()));
+        assert!(VRFOutput::eval(&vk, &sk, &x).verify(public_key.as_point(), &x));
+    }
+
+    #[test]
+    fn test_decompress_point_sign() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let point = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * Scalar::random(&mut rng);
+        let bytes = point.compress().to_bytes();
+        assert_eq!(Ok(point), decompress_point(&bytes));
+
+        // Same `y`, opposite sign of `x`: the negated point.
+        let mut flipped = bytes;
+        flipped[31] ^= 0x80;
+        assert_eq!(Ok(-point), decompress_point(&flipped));
+        assert_eq!(flipped, (-point).compress().to_bytes());
+
+        // `x = 0` has no negative, so the identity with the sign bit set is
+        // a second, non-canonical encoding of it.
+        let mut identity = EdwardsPoint::identity().compress().to_bytes();
+        assert_eq!(Ok(EdwardsPoint::identity()), decompress_point(&identity));
+        identity[31] |= 0x80;
+        assert_eq!(Err(VrfError::InvalidPointEncoding), decompress_point(&identity));
+
+        // `y = p + 1` decodes to the same point as `y = 1` in dalek.
+        let mut unreduced = [0xff; 32];
+        unreduced[0] = 0xee;
+        unreduced[31] = 0x7f;
+        assert_eq!(Err(VrfError::InvalidPointEncoding), decompress_point(&unreduced));
+    }
+
+    #[test]
+    fn test_public_key_identity_rejected() {
+        let identity = curve25519_dalek::edwards::EdwardsPoint::default();
+        assert_eq!(Err(VrfError::IdentityPoint), PublicKey::from_bytes(&identity.compress().to_bytes()));
+
+        let mut rng = rand::rngs::ThreadRng::default();
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&identity, &Scalar::zero(), &x);
+        assert!(!output.verify(&identity, &x));
+    }
+
+    #[test]
+    fn test_public_key_small_order_rejected() {
+        let small = curve25519_dalek::constants::EIGHT_TORSION[1];
+        assert_eq!(Err(VrfError::NotInSubgroup), PublicKey::from_bytes(&small.compress().to_bytes()));
+
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        assert_eq!(Err(VrfError::NotInSubgroup), PublicKey::from_bytes(&(vk + small).compress().to_bytes()));
+    }
+
+    #[test]
+    fn test_small_order_points_rejected() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let bytes = VRFOutput::eval(&vk, &sk, &x).to_proof_bytes();
+        for (point, encoding) in curve25519_dalek::constants::EIGHT_TORSION.iter().zip(&SMALL_ORDER_ENCODINGS) {
+            assert_eq!(encoding, point.compress().as_bytes());
+            assert!(is_small_order(point));
+            assert!(PublicKey::from_bytes(encoding).is_err());
+            assert!(!VRFOutput::eval(point, &sk, &x).verify(point, &x));
+
+            let mut tampered = bytes;
+            tampered[..32].copy_from_slice(encoding);
+            assert_eq!(Err(VrfError::NotInSubgroup), VRFOutput::from_proof_bytes(&tampered));
+        }
+        assert!(!is_small_order(&vk));
+        assert!(!is_small_order(&(vk + curve25519_dalek::constants::EIGHT_TORSION[1])));
+    }
+
+    #[test]
+    fn test_verify_split() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let VRFOutput { gamma, c, s, y } = VRFOutput::eval(&vk, &sk, &x);
+        assert!(verify_split(&gamma, &c, &s, &y, &vk, &x));
+        assert!(!verify_split(&gamma, &c, &s, &y, &vk, &Scalar::random(&mut rng)));
+    }
+
+    #[test]
+    fn test_clone_eq_debug() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        let rebuilt = VRFOutput {
+            gamma: output.gamma,
+            c: output.c,
+            s: output.s,
+            y: output.y.clone()
+        };
+        assert_eq!(output, rebuilt);
+        assert_eq!(output, output.clone());
+        assert_ne!(output, VRFOutput::eval(&vk, &sk, &x));
+        assert!(format!("{:?}", output).contains(&hex::encode(output.gamma.compress().as_bytes())));
+    }
+
+    #[test]
+    fn test_proof_bytes_roundtrip() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        let decoded = VRFOutput::from_proof_bytes(&output.to_proof_bytes()).unwrap();
+        assert_eq!(output, decoded);
+        assert!(decoded.verify(&vk, &x));
+    }
+
+    #[test]
+    fn test_display() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        let displayed = output.to_string();
+        assert_eq!(2 * VRFOutput::<32>::PROOF_LEN, displayed.len());
+        assert_eq!(Ok(output), VRFOutput::from_proof_slice(&hex::decode(displayed).unwrap()));
+
+        let short = ShortVRFOutput::eval_short(&vk, &sk, &x);
+        let displayed = short.to_string();
+        assert_eq!(displayed.to_lowercase(), displayed);
+        assert_eq!(Ok(short), ShortVRFOutput::from_short_proof_slice(&hex::decode(displayed).unwrap()));
+    }
+
+    #[test]
+    fn test_hex() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        let hex = output.to_hex();
+        assert_eq!(Ok(output.clone()), VRFOutput::from_hex(&hex));
+        assert_eq!(Ok(output), VRFOutput::from_hex(&hex.to_uppercase()));
+        assert_eq!(Err(VrfError::InvalidEncoding), VRFOutput::from_hex(&hex[1..]));
+        assert_eq!(Err(VrfError::InvalidEncoding), VRFOutput::from_hex(&format!("zz{}", &hex[2..])));
+        assert_eq!(Err(VrfError::InvalidLength), VRFOutput::from_hex(&hex[2..]));
+
+        let short = ShortVRFOutput::eval_short(&vk, &sk, &x);
+        assert_eq!(Ok(short.clone()), ShortVRFOutput::from_short_hex(&short.to_hex()));
+        assert_eq!(Err(VrfError::InvalidLength), ShortVRFOutput::from_short_hex(&VRFOutput::eval(&vk, &sk, &x).to_hex()));
+    }
+
+    #[test]
+    fn test_eval_committed() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let (output, opening) = VRFOutput::eval_committed(&vk, &sk, &x, b"contract");
+        assert!(output.verify(&vk, &x));
+        assert_eq!(VRFOutput::eval(&vk, &sk, &x).normalize_output(), output.normalize_output());
+        assert!(output.verify_commitment(&vk, &x, &opening, b"contract"));
+
+        assert!(!output.verify_commitment(&vk, &x, &opening, b"other contract"));
+        let other_opening = opening + curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+        assert!(!output.verify_commitment(&vk, &x, &other_opening, b"contract"));
+        assert!(!VRFOutput::eval(&vk, &sk, &x).verify_commitment(&vk, &x, &opening, b"contract"));
+    }
+
+    #[test]
+    fn test_proof_len() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+
+        let bytes = VRFOutput::eval(&vk, &sk, &x).to_proof_bytes();
+        assert_eq!(VRFOutput::<32>::PROOF_LEN, bytes.len());
+        assert!(VRFOutput::from_proof_slice(&bytes).unwrap().verify(&vk, &x));
+        assert_eq!(Err(VrfError::InvalidLength), VRFOutput::from_proof_slice(&[&bytes[..], &[0]].concat()));
+        assert_eq!(Err(VrfError::InvalidLength), VRFOutput::from_proof_slice(&bytes[1..]));
+
+        let bytes = ShortVRFOutput::eval_short(&vk, &sk, &x).to_proof_bytes();
+        assert_eq!(ShortVRFOutput::PROOF_LEN, bytes.len());
+        assert!(ShortVRFOutput::from_short_proof_slice(&bytes).unwrap().verify(&vk, &x));
+        assert_eq!(Err(VrfError::InvalidLength), ShortVRFOutput::from_short_proof_slice(&[&bytes[..], &[0]].concat()));
+    }
+
+    #[test]
+    fn test_normalize_output() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        let normalized: [u8; 64] = output.normalize_output();
+        assert_eq!(normalized, VRFOutput::eval(&vk, &sk, &x).normalize_output());
+        assert_ne!(output.y.as_slice(), normalized.as_slice());
+    }
+
+    #[test]
+    fn test_verify_and_output() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        assert_eq!(Some(output.y.as_slice()), output.verify_and_output(&vk, &x).as_ref().map(|y| &y[..]));
+
+        let mut tampered = output.clone();
+        tampered.s += Scalar::one();
+        assert_eq!(None, tampered.verify_and_output(&vk, &x));
+        assert_eq!(None, output.verify_and_output(&vk, &Scalar::random(&mut rng)));
+    }
+
+    #[cfg(not(feature = "no_panic"))]
+    #[test]
+    fn test_output_in_range() {
+        use std::num::NonZeroU64;
+
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let mut counts = [0usize; 3];
+        for _ in 0..300 {
+            let output = VRFOutput::eval(&vk, &sk, &Scalar::random(&mut rng));
+            let copy = VRFOutput::from_proof_bytes(&output.to_proof_bytes()).unwrap();
+            assert_eq!(output.output_u64(), copy.output_u64());
+            assert_eq!(output.output_in_range(3), copy.output_in_range(3));
+            assert_eq!(0, output.output_in_range(1));
+            assert_eq!(output.output_in_range(3), output.output_in_nonzero_range(NonZeroU64::new(3).unwrap()));
+            assert!(output.output_in_range(u64::MAX) < u64::MAX);
+            counts[output.output_in_range(3) as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| c > 50), "{:?}", counts);
+    }
+
+    #[test]
+    fn test_eval_with_nonce() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let k = Scalar::from(42u64);
+        let output = VRFOutput::eval_with_nonce(&vk, &sk, &x, &k).unwrap();
+        assert!(output.verify(&vk, &x));
+        assert_eq!(output, VRFOutput::eval_with_nonce(&vk, &sk, &x, &k).unwrap());
+        assert_eq!(output.y, VRFOutput::eval(&vk, &sk, &x).y);
+    }
+
+    #[test]
+    fn test_eval_with_zero_nonce() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        assert_eq!(Err(VrfError::ZeroNonce), VRFOutput::eval_with_nonce(&vk, &sk, &x, &Scalar::zero()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        let encoded = serde_cbor::to_vec(&output).unwrap();
+        let decoded: VRFOutput = serde_cbor::from_slice(&encoded).unwrap();
+        assert_eq!(output, decoded);
+        assert!(decoded.verify(&vk, &x));
+
+        let mut bytes = output.to_proof_bytes().to_vec();
+        bytes.extend_from_slice(&output.y);
+        let mut unreduced = bytes.clone();
+        unreduced[64..96].copy_from_slice(&[0xff; 32]);
+        let encoded = serde_cbor::to_vec(&serde_cbor::Value::Array(
+            unreduced.iter().map(|&b| serde_cbor::Value::Integer(b.into())).collect()
+        )).unwrap();
+        assert!(serde_cbor::from_slice::<VRFOutput>(&encoded).is_err());
+
+        bytes[159] ^= 1;
+        let encoded = serde_cbor::to_vec(&serde_cbor::Value::Array(
+            bytes.iter().map(|&b| serde_cbor::Value::Integer(b.into())).collect()
+        )).unwrap();
+        assert!(serde_cbor::from_slice::<VRFOutput>(&encoded).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_bincode_round_trip() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        let encoded = bincode::serialize(&output).unwrap();
+        let mut bytes = output.to_proof_bytes().to_vec();
+        bytes.extend_from_slice(&output.y);
+        assert_eq!(bytes, encoded);
+        let decoded: VRFOutput = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(output, decoded);
+        assert!(decoded.verify(&vk, &x));
+
+        let mut unreduced = bytes.clone();
+        unreduced[64..96].copy_from_slice(&[0xff; 32]);
+        assert!(bincode::deserialize::<VRFOutput>(&unreduced).is_err());
+        bytes[159] ^= 1;
+        assert!(bincode::deserialize::<VRFOutput>(&bytes).is_err());
+        assert!(bincode::deserialize::<VRFOutput>(&encoded[..159]).is_err());
+    }
+
+    #[test]
+    fn test_short_challenge() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = ShortVRFOutput::eval_short(&vk, &sk, &x);
+        assert!(output.verify(&vk, &x));
+        assert_eq!(VRFOutput::eval(&vk, &sk, &x).normalize_output(), output.normalize_output());
+
+        let bytes = output.to_proof_bytes();
+        assert_eq!(80, bytes.len());
+        let decoded = ShortVRFOutput::from_short_proof_bytes(&bytes).unwrap();
+        assert_eq!(output, decoded);
+        assert!(decoded.verify(&vk, &x));
+        assert!(!decoded.verify(&vk, &Scalar::random(&mut rng)));
+
+        let mut tampered = bytes;
+        tampered[40] ^= 1;
+        assert!(!ShortVRFOutput::from_short_proof_bytes(&tampered).unwrap().verify(&vk, &x));
+    }
+
+    #[test]
+    fn test_context() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let epoch1 = VRFOutput::eval_with_context(&vk, &sk, &x, b"epoch-1");
+        let epoch2 = VRFOutput::eval_with_context(&vk, &sk, &x, b"epoch-2");
+        assert_ne!(epoch1.y, epoch2.y);
+        assert!(epoch1.verify_with_context(&vk, &x, b"epoch-1"));
+        assert!(!epoch1.verify_with_context(&vk, &x, b"epoch-2"));
+        assert!(epoch2.verify_with_context(&vk, &x, b"epoch-2"));
+        assert!(!epoch2.verify(&vk, &x));
+
+        let plain = VRFOutput::eval(&vk, &sk, &x);
+        assert_eq!(plain.y, VRFOutput::eval_with_context(&vk, &sk, &x, b"").y);
+        assert!(plain.verify_with_context(&vk, &x, b""));
+    }
+
+    #[test]
+    fn test_eval_with_rng() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        let sk = Scalar::from_bytes_mod_order([3; 32]);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::from(7u64);
+        let output = VRFOutput::eval_with_rng(&mut ChaCha20Rng::from_seed([1; 32]), &vk, &sk, &x);
+        assert!(output.verify(&vk, &x));
+        assert_eq!(output, VRFOutput::eval_with_rng(&mut ChaCha20Rng::from_seed([1; 32]), &vk, &sk, &x));
+        let other = VRFOutput::eval_with_rng(&mut ChaCha20Rng::from_seed([2; 32]), &vk, &sk, &x);
+        assert_ne!(output, other);
+        assert_eq!(output.y, other.y);
+
+        let rng = || ChaCha20Rng::from_seed([1; 32]);
+        let context = VRFOutput::eval_with_context_with_rng(&mut rng(), &vk, &sk, &x, b"ctx");
+        assert!(context.verify_with_context(&vk, &x, b"ctx"));
+        assert_eq!(context, VRFOutput::eval_with_context_with_rng(&mut rng(), &vk, &sk, &x, b"ctx"));
+        let session = VRFOutput::eval_with_session_with_rng(&mut rng(), &vk, &sk, &x, b"sid");
+        assert_eq!(session, VRFOutput::eval_with_session_with_rng(&mut rng(), &vk, &sk, &x, b"sid"));
+        let short = ShortVRFOutput::eval_short_with_rng(&mut rng(), &vk, &sk, &x);
+        assert_eq!(short.to_proof_bytes(), ShortVRFOutput::eval_short_with_rng(&mut rng(), &vk, &sk, &x).to_proof_bytes());
+        let signed = VrfSigner::new(&vk, &sk).update(b"msg").finalize_with_rng(&mut rng());
+        assert!(signed.verify_message(&vk, b"msg"));
+        assert_eq!(signed, VrfSigner::new(&vk, &sk).update(b"msg").finalize_with_rng(&mut rng()));
+    }
+
+    #[test]
+    fn test_find_duplicate_gammas() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sks: Vec<_> = (0..3).map(|_| Scalar::random(&mut rng)).collect();
+        let x = Scalar::random(&mut rng);
+        let eval = |sk: &Scalar| VRFOutput::eval(&(curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk), sk, &x);
+        let mut outputs: Vec<_> = sks.iter().map(eval).collect();
+        assert_eq!(None, find_duplicate_gammas(&outputs));
+        outputs.push(eval(&sks[0]));
+        assert_ne!(outputs[0], outputs[3]);
+        assert_eq!(Some((0, 3)), find_duplicate_gammas(&outputs));
+    }
+
+    #[test]
+    fn test_verify_proof_only() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let mut output = VRFOutput::eval(&vk, &sk, &x);
+        output.y = vec![0xff; 64];
+        assert!(!output.verify(&vk, &x));
+        assert!(output.verify_proof_only(&vk, &x));
+        assert!(!output.verify_proof_only(&vk, &Scalar::random(&mut rng)));
+        output.s += Scalar::one();
+        assert!(!output.verify_proof_only(&vk, &x));
+    }
+
+    #[test]
+    fn test_verify_with_base() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let h = VRFOutput::hash_point(&x);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        assert!(output.verify_with_base(&vk, &h));
+        assert!(!output.verify_with_base(&vk, &VRFOutput::hash_point(&Scalar::random(&mut rng))));
+
+        let short = ShortVRFOutput::eval_short(&vk, &sk, &x);
+        assert_eq!(short.verify(&vk, &x), short.verify_with_base(&vk, &h));
+        let tampered = VRFOutput::eval(&vk, &Scalar::random(&mut rng), &x);
+        assert_eq!(tampered.verify(&vk, &x), tampered.verify_with_base(&vk, &h));
+    }
+
+    #[test]
+    fn test_session() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let first = VRFOutput::eval_with_session(&vk, &sk, &x, b"session-1");
+        let second = VRFOutput::eval_with_session(&vk, &sk, &x, b"session-2");
+        assert_ne!(first.y, second.y);
+        assert_ne!(first.y, VRFOutput::eval(&vk, &sk, &x).y);
+        assert_eq!(first.y, VRFOutput::eval_with_session(&vk, &sk, &x, b"session-1").y);
+        assert!(first.verify_with_session(&vk, &x, b"session-1"));
+        assert!(!first.verify_with_session(&vk, &x, b"session-2"));
+        assert!(!first.verify_with_context(&vk, &x, b"session-1"));
+
+        // the framing the session id once had, passed as a context
+        let colliding = [&b"ec-vrf-session"[..], b"session-1"].concat();
+        assert!(!first.verify_with_context(&vk, &x, &colliding));
+        assert_ne!(first.y, VRFOutput::eval_with_context(&vk, &sk, &x, &colliding).y);
+        assert!(!first.verify(&vk, &x));
+
+        // reproducing the current framing needs its first 32 bytes as `x`
+        let framed = super::session_bytes(&x, b"session-1");
+        assert!(Scalar::from_canonical_bytes(framed[..32].try_into().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_hash_to_curve() {
+        let h = hash_to_curve(b"abc");
+        assert_eq!(h, curve25519_dalek::edwards::EdwardsPoint::hash_from_bytes::<sha2::Sha512>(b"abc"));
+        assert_eq!("4b4c5ea4866236eb57b5c3765655d5e4b584740d15264bfd6f6a8738aadf4703", hex::encode(h.compress().as_bytes()));
+
+        let x = Scalar::from(7u64);
+        assert_eq!(VRFOutput::hash_point(&x), hash_to_curve(&x.to_bytes()));
+    }
+
+    #[test]
+    fn test_verification_key() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let key = VerificationKey::new(&vk).unwrap();
+        for i in 0..8u8 {
+            let msg = [i; 40];
+            let output = VRFOutput::eval_message(&vk, &sk, &msg);
+            assert!(key.verify(&msg, &output));
+            assert_eq!(output.verify_message(&vk, &[i + 1; 40]), key.verify(&[i + 1; 40], &output));
+        }
+
+        let other = VRFOutput::eval_message(&vk, &Scalar::random(&mut rng), b"msg");
+        assert_eq!(other.verify_message(&vk, b"msg"), key.verify(b"msg", &other));
+        assert!(!key.verify(b"msg", &other));
+
+        let public_key = PublicKey::from_bytes(&vk.compress().to_bytes()).unwrap();
+        assert!(VerificationKey::from(public_key).verify(&[0; 40], &VRFOutput::eval_message(&vk, &sk, &[0; 40])));
+        assert_eq!(Err(VrfError::IdentityPoint), VerificationKey::new(&EdwardsPoint::identity()).map(|k| *k.as_point()));
+    }
+
+    #[test]
+    fn test_secret_key_from_seed() {
+        let seed = [7u8; 32];
+        let key = SecretKey::from_seed(&seed);
+        assert_eq!(key.as_scalar(), SecretKey::from_seed(&seed).as_scalar());
+        assert_eq!(key.public_key(), SecretKey::from_seed(&seed).public_key());
+        assert_ne!(key.public_key(), SecretKey::from_seed(&[8u8; 32]).public_key());
+
+        let bytes = key.as_scalar().to_bytes();
+        assert_eq!(0, bytes[0] & 7);
+        assert_eq!(64, bytes[31] & 192);
+
+        let vk = key.public_key().as_point();
+        assert_eq!(*vk, curve25519_dalek::constants::ED25519_BASEPOINT_POINT * key.as_scalar());
+        let x = Scalar::from(3u64);
+        assert!(VRFOutput::eval(vk, key.as_scalar(), &x).verify(vk, &x));
+    }
+
+    #[test]
+    fn test_expand() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        let values = output.expand(8);
+        assert_eq!(8, values.len());
+        assert_eq!(values, VRFOutput::eval(&vk, &sk, &x).expand(8));
+        assert_eq!(values[..3], output.expand(3)[..]);
+        assert!(values.iter().enumerate().all(|(i, a)| values[i + 1..].iter().all(|b| a != b)));
+        assert!(values.iter().all(|v| v[..] != output.y[..]));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        let fingerprint = output.fingerprint();
+        assert_eq!(16, fingerprint.len());
+        assert_eq!(fingerprint, output.clone().fingerprint());
+        assert_ne!(fingerprint, VRFOutput::eval(&vk, &sk, &x).fingerprint());
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let mut msg = vec![0u8; 1 << 20];
+        rng.fill(&mut msg[..]);
+
+        let mut signer = VrfSigner::new(&vk, &sk);
+        let mut rest = &msg[..];
+        while !rest.is_empty() {
+            let (chunk, tail) = rest.split_at(rng.gen_range(0, rest.len()) + 1);
+            signer = signer.update(chunk);
+            rest = tail;
+        }
+        let streamed = signer.finalize();
+        let one_shot = VRFOutput::eval_message(&vk, &sk, &msg);
+        assert_eq!(one_shot.gamma, streamed.gamma);
+        assert_eq!(one_shot.y, streamed.y);
+        assert!(streamed.verify_message(&vk, &msg));
+
+        let (head, tail) = msg.split_at(1000);
+        assert!(VrfVerifier::new(&vk, &one_shot).update(head).update(tail).finalize());
+        assert!(!VrfVerifier::new(&vk, &one_shot).update(head).finalize());
+    }
+
+    #[test]
+    fn test_verify_with_metadata() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        let metadata = output.verify_with_metadata(&vk, &x).unwrap();
+        assert_eq!("ed25519", metadata.curve);
+        assert_eq!("ec-vrf", metadata.suite);
+        assert_eq!(output.y.len(), metadata.output_len);
+        assert_eq!(96, metadata.proof_len);
+        assert_eq!(output.to_proof_bytes().len(), metadata.proof_len);
+        assert_eq!(Err(VrfError::ProofRejected), output.verify_with_metadata(&vk, &Scalar::random(&mut rng)));
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_verify_with_metrics() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+
+        // subgroup checks on vk and gamma, two muls and one add each for u and
+        // v; hash_point, hash_challenge and hash_output
+        let (valid, ops) = output.verify_with_metrics(&vk, &x);
+        assert!(valid);
+        assert_eq!(6, ops.point_muls);
+        assert_eq!(2, ops.point_adds);
+        assert_eq!(3, ops.hashes);
+    }
+
+    #[test]
+    fn test_hash_point_torsion_free() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        for _ in 0..32 {
+            let h = VRFOutput::hash_point(&Scalar::random(&mut rng));
+            assert!(h.is_torsion_free());
+            assert!(!h.is_small_order());
+        }
+    }
+
+    #[test]
+    fn test_base_point_collision_rederived() {
+        use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, edwards::EdwardsPoint};
+        use sha2::Sha512;
+
+        let bytes = [7u8; 32];
+        let map = |b: &[u8]| if b == bytes {
+            ED25519_BASEPOINT_POINT
+        } else {
+            EdwardsPoint::hash_from_bytes::<Sha512>(b)
+        };
+        let h = VRFOutput::hash_bytes_to_point_with(&bytes, map);
+        assert_ne!(ED25519_BASEPOINT_POINT, h);
+        assert_eq!(EdwardsPoint::hash_from_bytes::<Sha512>(&[&bytes[..], &1u32.to_le_bytes()].concat()), h);
+        assert_eq!(EdwardsPoint::hash_from_bytes::<Sha512>(&bytes), VRFOutput::hash_bytes_to_point(&bytes));
+    }
+
+    #[test]
+    fn test_identity_base_rejected() {
+        use sha2::Sha512;
+
+        let bytes = [7u8; 32];
+        let map = |b: &[u8]| if b == bytes {
+            EdwardsPoint::identity()
+        } else {
+            EdwardsPoint::hash_from_bytes::<Sha512>(b)
+        };
+        let h = VRFOutput::hash_bytes_to_point_with(&bytes, map);
+        assert!(!is_small_order(&h));
+        assert_eq!(EdwardsPoint::hash_from_bytes::<Sha512>(&[&bytes[..], &1u32.to_le_bytes()].concat()), h);
+
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let degenerate = VRFOutput::<32>::eval_with_base(&mut rng, &vk, &sk, &EdwardsPoint::identity());
+        assert!(!degenerate.verify_with_base(&vk, &EdwardsPoint::identity()));
+    }
+
+    #[test]
+    fn test_zero_and_empty_inputs_accepted() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let zero = VRFOutput::eval(&vk, &sk, &Scalar::zero());
+        assert!(zero.verify(&vk, &Scalar::zero()));
+        assert!(zero.verify_and_output(&vk, &Scalar::zero()).is_some());
+        assert!(!zero.verify(&vk, &Scalar::one()));
+
+        let empty = VRFOutput::eval_message(&vk, &sk, b"");
+        assert!(empty.verify_message(&vk, b""));
+        assert!(!empty.verify_message(&vk, &[0]));
+        let (y, proof) = EcVrf.eval(&sk, b"");
+        assert!(EcVrf.verify(&vk, b"", &proof));
+        assert_eq!(empty.y, y);
+    }
+
+    #[test]
+    fn test_non_canonical_scalar_rejected() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let mut bytes = VRFOutput::eval(&vk, &sk, &x).to_proof_bytes();
+
+        // s + l encodes the same residue but is not reduced
+        let l = curve25519_dalek::constants::BASEPOINT_ORDER.to_bytes();
+        let mut carry = 0u16;
+        for (b, l) in bytes[64..].iter_mut().zip(l.iter()) {
+            let sum = *b as u16 + *l as u16 + carry;
+            *b = sum as u8;
+            carry = sum >> 8;
+        }
+        assert_eq!(0, carry);
+        assert_eq!(Err(VrfError::NonCanonicalScalar), VRFOutput::from_proof_bytes(&bytes));
+    }
+
+    #[test]
+    fn test_torsion_gamma_same_output_but_rejected() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+
+        // Adding a torsion component to gamma leaves y unchanged, which is why
+        // verify and decoding both insist on a torsion-free gamma.
+        for torsion in curve25519_dalek::constants::EIGHT_TORSION.iter().skip(1) {
+            let gamma = output.gamma + torsion;
+            assert_eq!(output.y, VRFOutput::hash_output(&gamma.mul_by_cofactor()));
+            assert!(!verify_split(&gamma, &output.c, &output.s, &output.y, &vk, &x));
+        }
+        assert!(verify_split(&output.gamma, &output.c, &output.s, &output.y, &vk, &x));
+    }
+
+    #[test]
+    fn test_invalid_point_encoding_rejected() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let mut bytes = VRFOutput::eval(&vk, &sk, &Scalar::random(&mut rng)).to_proof_bytes();
+        // y = 2 has no matching x on the curve
+        bytes[..32].copy_from_slice(&[0u8; 32]);
+        bytes[0] = 2;
+        assert_eq!(Err(VrfError::InvalidPointEncoding), VRFOutput::from_proof_bytes(&bytes));
+    }
+
+    #[test]
+    fn test_torsion_gamma_rejected() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let output = VRFOutput::eval(&vk, &sk, &Scalar::random(&mut rng));
+        let torsion = curve25519_dalek::constants::EIGHT_TORSION[1];
+        let mut bytes = output.to_proof_bytes();
+        bytes[..32].copy_from_slice((output.gamma + torsion).compress().as_bytes());
+        assert_eq!(Err(VrfError::NotInSubgroup), VRFOutput::from_proof_bytes(&bytes));
+    }
+
+    #[test]
+    fn test_verify_any() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let output = VRFOutput::eval_message(&vk, &sk, b"ticket 2");
+        let candidates: [&[u8]; 4] = [b"ticket 0", b"ticket 1", b"ticket 2", b"ticket 3"];
+        assert_eq!(Some(2), output.verify_any(&vk, &candidates));
+        assert_eq!(None, output.verify_any(&vk, &[candidates[0], candidates[3]]));
+        assert_eq!(None, output.verify_any(&vk, &[]));
+        let other_vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * Scalar::random(&mut rng);
+        assert_eq!(None, output.verify_any(&other_vk, &candidates));
     }
 
 //    fn bench_ec_vrf(repetition: usize) -> (Duration, Duration) {
@@ -0,0 +1,126 @@
+use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_POINT, ristretto::RistrettoPoint, scalar::Scalar, traits::IsIdentity};
+use rand::{rngs::OsRng, CryptoRng, RngCore};
+use sha2::{Sha512, Digest};
+use std::fmt;
+
+use crate::transcript::TranscriptHasher;
+
+/// The `ec_vrf` construction over Ristretto255. Every `RistrettoPoint` lies in
+/// the prime-order group, so unlike the Edwards version no cofactor clearing
+/// or subgroup checks are needed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct VRFOutput {
+    gamma: RistrettoPoint,
+    c: Scalar,
+    s: Scalar,
+    y: Vec<u8>
+}
+
+impl fmt::Debug for VRFOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VRFOutput")
+            .field("gamma", &hex::encode(self.gamma.compress().as_bytes()))
+            .field("c", &hex::encode(self.c.as_bytes()))
+            .field("s", &hex::encode(self.s.as_bytes()))
+            .field("y", &hex::encode(&self.y))
+            .finish()
+    }
+}
+
+impl VRFOutput {
+    fn hash_challenge(
+        h: &RistrettoPoint,
+        vk: &RistrettoPoint,
+        gamma: &RistrettoPoint,
+        gk: &RistrettoPoint,
+        hk: &RistrettoPoint
+    ) -> Scalar {
+        let digest = [&RISTRETTO_BASEPOINT_POINT, h, vk, gamma, gk, hk]
+            .iter()
+            .fold(TranscriptHasher::new(), |t, p| t.append(p.compress().as_bytes()))
+            .finalize();
+        Scalar::from_bytes_mod_order_wide(&digest)
+    }
+
+    // As in `ec_vrf`, re-derive `h` if it collides with the base point.
+    fn hash_point(x: &Scalar) -> RistrettoPoint {
+        let mut h = RistrettoPoint::hash_from_bytes::<Sha512>(x.as_bytes());
+        let mut ctr = 0u32;
+        while h == RISTRETTO_BASEPOINT_POINT {
+            ctr += 1;
+            h = RistrettoPoint::hash_from_bytes::<Sha512>(&[x.as_bytes(), &ctr.to_le_bytes()[..]].concat());
+        }
+        h
+    }
+
+    fn hash_output(gamma: &RistrettoPoint) -> Vec<u8> {
+        Sha512::digest(gamma.compress().as_bytes()).to_vec()
+    }
+
+    pub fn eval(vk: &RistrettoPoint, sk: &Scalar, x: &Scalar) -> Self {
+        Self::eval_with_rng(&mut OsRng, vk, sk, x)
+    }
+
+    pub fn eval_with_rng<R: RngCore + CryptoRng>(rng: &mut R, vk: &RistrettoPoint, sk: &Scalar, x: &Scalar) -> Self {
+        let k = Scalar::random(rng);
+        let h = Self::hash_point(x);
+        let gamma = h * sk;
+        let c = Self::hash_challenge(&h, vk, &gamma, &(RISTRETTO_BASEPOINT_POINT * k), &(h * k));
+        let s = k - c * sk;
+        let y = Self::hash_output(&gamma);
+        Self { gamma, c, s, y }
+    }
+
+    pub fn verify(&self, vk: &RistrettoPoint, x: &Scalar) -> bool {
+        if vk.is_identity() {
+            return false;
+        }
+        let h = Self::hash_point(x);
+        let u = vk * self.c + RISTRETTO_BASEPOINT_POINT * self.s;
+        let v = self.gamma * self.c + h * self.s;
+        self.c == Self::hash_challenge(&h, vk, &self.gamma, &u, &v)
+            && self.y == Self::hash_output(&self.gamma)
+    }
+
+    pub fn output(&self) -> &[u8] {
+        &self.y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_POINT, ristretto::RistrettoPoint, scalar::Scalar, traits::Identity};
+    use super::VRFOutput;
+
+    #[test]
+    fn test_valid() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = RISTRETTO_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+        assert!(output.verify(&vk, &x));
+        assert_eq!(output.output(), VRFOutput::eval(&vk, &sk, &x).output());
+    }
+
+    #[test]
+    fn test_tampered() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = Scalar::random(&mut rng);
+        let vk = RISTRETTO_BASEPOINT_POINT * sk;
+        let x = Scalar::random(&mut rng);
+        let output = VRFOutput::eval(&vk, &sk, &x);
+
+        assert!(!output.verify(&vk, &Scalar::random(&mut rng)));
+        assert!(!output.verify(&(vk + RISTRETTO_BASEPOINT_POINT), &x));
+        assert!(!VRFOutput { gamma: output.gamma + RISTRETTO_BASEPOINT_POINT, ..output.clone() }.verify(&vk, &x));
+        assert!(!VRFOutput { c: output.c + Scalar::one(), ..output.clone() }.verify(&vk, &x));
+        assert!(!VRFOutput { s: output.s + Scalar::one(), ..output.clone() }.verify(&vk, &x));
+        let mut y = output.y.clone();
+        y[0] ^= 1;
+        assert!(!VRFOutput { y, ..output.clone() }.verify(&vk, &x));
+
+        let identity = RistrettoPoint::identity();
+        assert!(!VRFOutput::eval(&identity, &Scalar::zero(), &x).verify(&identity, &x));
+    }
+}
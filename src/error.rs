@@ -0,0 +1,88 @@
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum VrfError {
+    #[error("secret key is zero")]
+    ZeroSecretKey,
+    #[error("nonce is zero")]
+    ZeroNonce,
+    #[error("bytes do not encode a curve point")]
+    InvalidPointEncoding,
+    #[error("scalar encoding is not reduced modulo the group order")]
+    NonCanonicalScalar,
+    #[error("point is the identity")]
+    IdentityPoint,
+    #[error("point is not in the prime-order subgroup")]
+    NotInSubgroup,
+    #[error("encoding has the wrong length")]
+    InvalidLength,
+    #[error("string is not valid hex")]
+    InvalidEncoding,
+    #[error("encoding is for a different curve")]
+    WrongCurve,
+    #[error("unsupported encoding version")]
+    UnsupportedVersion,
+    #[error("output does not match the proof")]
+    OutputMismatch,
+    #[error("proof rejected")]
+    ProofRejected,
+    #[error("batch exceeds the maximum batch size")]
+    BatchTooLarge,
+    #[error("bloom filter needs at least one bit and one hash")]
+    EmptyBloomFilter,
+}
+
+/// `hex::decode`, failing with `InvalidEncoding` on odd-length or non-hex
+/// input.
+pub(crate) fn decode_hex(s: &str) -> Result<Vec<u8>, VrfError> {
+    hex::decode(s).map_err(|_| VrfError::InvalidEncoding)
+}
+
+// Errors about the caller's own arguments map to `InvalidInput`; everything
+// else comes from bytes that failed to decode or verify.
+#[cfg(feature = "std")]
+impl From<VrfError> for std::io::Error {
+    fn from(err: VrfError) -> Self {
+        let kind = match err {
+            VrfError::ZeroSecretKey
+            | VrfError::ZeroNonce
+            | VrfError::BatchTooLarge
+            | VrfError::EmptyBloomFilter => std::io::ErrorKind::InvalidInput,
+            _ => std::io::ErrorKind::InvalidData
+        };
+        std::io::Error::new(kind, err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EpochError {
+    #[error("proof {0} does not verify")]
+    InvalidProof(usize),
+    #[error("proof {0} is from an unregistered key")]
+    UnregisteredKey(usize),
+    #[error("proof {0} repeats a key that already submitted a proof")]
+    DuplicateKey(usize),
+    #[error("{0} registered keys submitted no proof")]
+    MissingKeys(usize),
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::io;
+
+    use super::VrfError;
+
+    #[test]
+    fn test_into_io_error() {
+        fn decode(bytes: &[u8]) -> io::Result<()> {
+            match bytes.len() {
+                32 => Ok(()),
+                _ => Err(VrfError::InvalidLength)?
+            }
+        }
+        let err = decode(&[0; 31]).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        assert_eq!(Some(&VrfError::InvalidLength), err.get_ref().and_then(|e| e.downcast_ref()));
+        assert_eq!(io::ErrorKind::InvalidInput, io::Error::from(VrfError::ZeroSecretKey).kind());
+    }
+}
@@ -1,2 +1,66 @@
+// Compile test for `no_panic`: the panicking `eval` must not exist.
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::{Ed25519, Point};
+use vrf::rka_vrf::{Generators, KeyPair, VRFOutput};
+
+let params = Generators::<Ed25519>::from_seed(b\"no_panic\");
+let keypair = KeyPair::<Ed25519>::generate();
+let x = Point::<Ed25519>::generator().to_point();
+VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+```")]
+// The same for every other gated API, one compile test each.
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::Ed25519;
+let _ = vrf::rka_vrf::VRFOutput::<Ed25519>::eval_prehashed;
+```")]
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::Ed25519;
+let _ = vrf::rka_vrf::VRFOutput::<Ed25519>::eval_split;
+```")]
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::Ed25519;
+let _ = vrf::rka_vrf::VRFOutput::<Ed25519>::eval_bytes;
+```")]
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::Ed25519;
+let _ = vrf::rka_vrf::VRFOutput::<Ed25519>::eval_encrypted;
+```")]
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::Ed25519;
+let _ = vrf::rka_vrf::VRFOutput::<Ed25519>::eval_encrypted_with_rng::<rand::rngs::OsRng>;
+```")]
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::Ed25519;
+let _ = vrf::rka_vrf::InversionProof::<Ed25519>::prove;
+```")]
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::Ed25519;
+let _ = vrf::rka_vrf::InversionProof::<Ed25519>::prove_with_rng::<rand::rngs::OsRng>;
+```")]
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::Ed25519;
+let _ = vrf::rka_vrf::InversionProof::<Ed25519>::prove_deterministic;
+```")]
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::Ed25519;
+let _ = vrf::rka_vrf::RkaVrf::<Ed25519>::new;
+```")]
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::Ed25519;
+let _ = vrf::ec_vrf::VRFOutput::<32>::output_in_range;
+```")]
+#[cfg_attr(feature = "no_panic", doc = "```compile_fail
+use curv::elliptic::curves::Ed25519;
+let _ = vrf::revocation::BloomFilter::new;
+```")]
 pub mod rka_vrf;
-pub mod ec_vrf;
\ No newline at end of file
+pub mod ec_vrf;
+pub mod selection;
+pub mod revocation;
+pub mod error;
+pub mod metrics;
+pub mod traits;
+pub mod transcript;
+mod output;
+#[cfg(feature = "wasm")]
+pub mod wasm;
\ No newline at end of file
@@ -0,0 +1,7 @@
+/// Work done by a single verification, for profiling slow verifiers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    pub point_muls: usize,
+    pub point_adds: usize,
+    pub hashes: usize,
+}
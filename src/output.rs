@@ -0,0 +1,79 @@
+use sha2::{Digest, Sha512};
+
+/// Final hashing shared by both schemes' `normalize_output`, so outputs can be
+/// stored in one uniform 64-byte format.
+pub(crate) fn normalize(output: &[u8; 64]) -> [u8; 64] {
+    let digest = Sha512::new()
+        .chain(b"vrf-normalized-output")
+        .chain(output)
+        .finalize();
+    let mut normalized = [0u8; 64];
+    normalized.copy_from_slice(&digest);
+    normalized
+}
+
+/// `n` independent 64-byte values `Sha512("vrf-expand" || i || point)` from
+/// the point an output is derived from, with `i` a little-endian `u64`.
+pub(crate) fn expand(point: &[u8], n: usize) -> Vec<[u8; 64]> {
+    (0..n as u64).map(|i| {
+        let digest = Sha512::new()
+            .chain(b"vrf-expand")
+            .chain(i.to_le_bytes())
+            .chain(point)
+            .finalize();
+        let mut value = [0u8; 64];
+        value.copy_from_slice(&digest);
+        value
+    }).collect()
+}
+
+// The two schemes are different VRFs, so their outputs never coincide; these
+// tests pin down the primitives they are meant to share.
+#[cfg(all(test, not(feature = "no_panic")))]
+mod tests {
+    use curv::{arithmetic::Converter, elliptic::curves::{Ed25519, Point, Scalar}, BigInt};
+    use sha2::{Digest, Sha512};
+
+    use crate::{ec_vrf, rka_vrf};
+    use crate::rka_vrf::{Generators, KeyPair};
+
+    use super::normalize;
+
+    // Both reduce the full 512-bit digest mod l. They legitimately
+    // differ in byte order: dalek reads the digest little-endian, curv's
+    // `BigInt` big-endian.
+    #[test]
+    fn test_hash_to_scalar_agrees_up_to_byte_order() {
+        for i in 0u8..16 {
+            let rka = rka_vrf::reduce_wide(Sha512::new().chain([i; 40]), Scalar::<Ed25519>::group_order());
+
+            let mut digest: [u8; 64] = Sha512::digest(&[i; 40]).as_slice().try_into().unwrap();
+            digest.reverse();
+            let ec = curve25519_dalek::scalar::Scalar::from_bytes_mod_order_wide(&digest);
+            let mut ec_bytes = ec.to_bytes();
+            ec_bytes.reverse();
+            assert_eq!(rka, BigInt::from_bytes(&ec_bytes));
+        }
+    }
+
+    // Both outputs are 64-byte Sha512 digests (of `gamma * 8` in ec_vrf, of
+    // `(x, u)` in rka_vrf), and both normalize through the same final hash.
+    #[test]
+    fn test_output_hash_and_normalization() {
+        let mut rng = rand::rngs::ThreadRng::default();
+        let sk = curve25519_dalek::scalar::Scalar::random(&mut rng);
+        let vk = curve25519_dalek::constants::ED25519_BASEPOINT_POINT * sk;
+        let ec_x = curve25519_dalek::scalar::Scalar::random(&mut rng);
+        let ec = ec_vrf::VRFOutput::eval(&vk, &sk, &ec_x);
+        let ec_y = ec.verify_and_output(&vk, &ec_x).unwrap();
+        assert_eq!(sha2::Sha512::output_size(), ec_y.len());
+        assert_eq!(normalize(&ec_y), ec.normalize_output());
+
+        let keypair = KeyPair::<Ed25519>::generate();
+        let params = Generators::<Ed25519>::from_seed(b"consistency");
+        let x = Point::<Ed25519>::generator() * &Scalar::<Ed25519>::random();
+        let rka = rka_vrf::VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+        let rka_y = rka.output_value().to_bytes_array::<64>().unwrap();
+        assert_eq!(normalize(&rka_y), rka.normalize_output());
+    }
+}
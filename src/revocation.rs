@@ -0,0 +1,87 @@
+use sha2::{Digest, Sha512};
+use std::collections::HashSet;
+
+use crate::error::VrfError;
+
+/// Bloom filter over revoked key encodings. Keeps the exact set alongside the
+/// bits so a filter hit can be confirmed before a key is treated as revoked.
+#[derive(Clone, Debug)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: usize,
+    num_hashes: u32,
+    keys: HashSet<Vec<u8>>,
+}
+
+impl BloomFilter {
+    /// Panics if `num_bits` or `num_hashes` is zero; see `try_new`. Not
+    /// available with the `no_panic` feature.
+    #[cfg(not(feature = "no_panic"))]
+    pub fn new(num_bits: usize, num_hashes: u32) -> Self {
+        Self::try_new(num_bits, num_hashes).unwrap()
+    }
+
+    pub fn try_new(num_bits: usize, num_hashes: u32) -> Result<Self, VrfError> {
+        if num_bits == 0 || num_hashes == 0 {
+            return Err(VrfError::EmptyBloomFilter);
+        }
+        Ok(Self {
+            bits: vec![0; num_bits.div_ceil(64)],
+            num_bits,
+            num_hashes,
+            keys: HashSet::new(),
+        })
+    }
+
+    pub fn insert(&mut self, key: &[u8]) {
+        let indices: Vec<_> = self.indices(key).collect();
+        for i in indices {
+            self.bits[i / 64] |= 1 << (i % 64);
+        }
+        self.keys.insert(key.to_vec());
+    }
+
+    /// May return false positives, never false negatives.
+    pub fn might_contain(&self, key: &[u8]) -> bool {
+        self.indices(key).all(|i| self.bits[i / 64] & (1 << (i % 64)) != 0)
+    }
+
+    pub fn contains(&self, key: &[u8]) -> bool {
+        self.might_contain(key) && self.keys.contains(key)
+    }
+
+    fn indices<'a>(&'a self, key: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let num_bits = self.num_bits;
+        (0..self.num_hashes).map(move |i| {
+            let digest = Sha512::new().chain(i.to_le_bytes()).chain(key).finalize();
+            u64::from_le_bytes(digest[..8].try_into().unwrap()) as usize % num_bits
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyOutcome {
+    Valid,
+    Invalid,
+    Revoked,
+}
+
+#[cfg(all(test, not(feature = "no_panic")))]
+mod tests {
+    use crate::error::VrfError;
+
+    use super::BloomFilter;
+
+    #[test]
+    fn test_false_positive_rejected_by_set() {
+        // A single bit makes every lookup a filter hit.
+        let mut filter = BloomFilter::new(1, 1);
+        filter.insert(b"revoked");
+        assert!(filter.contains(b"revoked"));
+        assert!(filter.might_contain(b"other"));
+        assert!(!filter.contains(b"other"));
+
+        assert_eq!(Some(VrfError::EmptyBloomFilter), BloomFilter::try_new(0, 1).err());
+        assert_eq!(Some(VrfError::EmptyBloomFilter), BloomFilter::try_new(1, 0).err());
+    }
+}
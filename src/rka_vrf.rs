@@ -1,6 +1,331 @@
-use curv::{elliptic::curves::{Scalar, Point, Curve}, cryptographic_primitives::hashing::DigestExt, BigInt};
+use curv::{elliptic::curves::{Scalar, Point, Curve, ECPoint, PointFromBytesError, Secp256k1}, cryptographic_primitives::hashing::DigestExt, arithmetic::{BitManipulation, Converter, Modulo}, BigInt};
+use rand::{rngs::OsRng, CryptoRng, RngCore};
 use sha2::Digest;
+use std::{borrow::Cow, collections::{HashMap, HashSet}, fmt};
 
+use crate::error::{decode_hex, EpochError, VrfError};
+use crate::metrics::OpCounts;
+use crate::revocation::{BloomFilter, VerifyOutcome};
+#[cfg(not(feature = "no_panic"))]
+use crate::traits::Vrf;
+use crate::transcript::TranscriptHasher;
+
+// 64 bytes reduced mod the group order keeps the bias negligible on any
+// supported curve.
+fn random_scalar<C: Curve, R: RngCore + CryptoRng>(rng: &mut R) -> Scalar<C> {
+    let mut bytes = [0u8; 64];
+    rng.fill_bytes(&mut bytes);
+    Scalar::<C>::from_bigint(&BigInt::from_bytes(&bytes))
+}
+
+// Some curv constructors (e.g. `Scalar::<Ed25519>::from_bytes`) keep a value
+// that is not reduced mod the group order; such a scalar multiplies correctly
+// but compares and serializes differently from its reduced form.
+fn reduce<C: Curve>(s: &Scalar<C>) -> Scalar<C> {
+    Scalar::<C>::from_bigint(&s.to_bigint())
+}
+
+// Inverse of a secret scalar as `s^(q - 2)` by square-and-multiply. curv's
+// `invert` is a `BigInt` extended-gcd inverse whose running time depends on
+// the value; here the sequence of operations depends only on the public group
+// order. The multiplications are curv's, so this is as constant-time as they
+// are. Like `invert`, returns `None` for zero.
+fn invert_ct<C: Curve>(s: &Scalar<C>) -> Option<Scalar<C>> {
+    if s.is_zero() {
+        return None;
+    }
+    let exponent = Scalar::<C>::group_order() - BigInt::from(2);
+    let mut acc = Scalar::<C>::from(1);
+    for i in (0..exponent.bit_length()).rev() {
+        acc = &acc * &acc;
+        if exponent.test_bit(i) {
+            acc = &acc * s;
+        }
+    }
+    Some(acc)
+}
+
+// curv's `Generator * Scalar` re-checks the order of the product, roughly
+// tripling its cost on Ed25519. `as_point` borrows the static point curv
+// already keeps, so multiplying through it skips that check.
+fn generator<C: Curve>() -> &'static Point<C> {
+    Point::<C>::generator().as_point()
+}
+
+// Reducing a hash mod q is biased by about 2^-(extra bits), so take at least
+// 128 bits beyond the order. A single Sha512 block covers every curve curv
+// ships; longer outputs extend it in counter mode.
+fn wide_hash_len(order: &BigInt) -> usize {
+    (order.bit_length() + 128).div_ceil(8).max(64)
+}
+
+pub(crate) fn reduce_wide(digest: sha2::Sha512, order: &BigInt) -> BigInt {
+    let len = wide_hash_len(order);
+    let mut bytes = digest.clone().finalize().to_vec();
+    let mut ctr = 1u32;
+    while bytes.len() < len {
+        bytes.extend_from_slice(&digest.clone().chain(ctr.to_be_bytes()).finalize());
+        ctr += 1;
+    }
+    BigInt::from_bytes(&bytes[..len]).modulus(order)
+}
+
+// Each point compressed and framed by `TranscriptHasher`, kept as bytes.
+fn transcript_bytes<'p, C: Curve>(points: impl IntoIterator<Item = &'p Point<C>>) -> Vec<u8> {
+    points.into_iter()
+        .fold(TranscriptHasher::with_sink(Vec::new()), |t, p| t.append(&p.to_bytes(true)))
+        .into_inner()
+}
+
+fn hash_to_scalar<C: Curve>(transcript: &[u8]) -> Scalar<C> {
+    Scalar::<C>::from_bigint(&reduce_wide(sha2::Sha512::new().chain(transcript), Scalar::<C>::group_order()))
+}
+
+fn point_len<C: Curve>() -> usize {
+    Point::<C>::generator().to_bytes(true).len()
+}
+
+fn scalar_len<C: Curve>() -> usize {
+    Scalar::<C>::zero().to_bytes().len()
+}
+
+/// Leading byte of `InversionProof::to_bytes`. The high bit marks a versioned
+/// encoding and the low bits hold the version. Version 1 had no version byte
+/// and starts directly with the curve discriminant, which is always below
+/// `0x80`. Version 3 binds `VRFOutput`'s statement into the challenge;
+/// `VRFOutput` proofs of versions 1 and 2 still verify against the unbound
+/// challenge they were made with. A bare `InversionProof` is unchanged across
+/// all three.
+pub const PROOF_VERSION: u8 = 0x83;
+
+const PROOF_VERSION_2: u8 = 0x82;
+
+// Follows the version byte of `InversionProof::to_bytes`, so a proof cannot
+// be decoded as one for a different curve with the same encoding lengths.
+fn curve_id<C: Curve>() -> u8 {
+    match C::CURVE_NAME {
+        "ed25519" => 1,
+        "secp256k1" => 2,
+        "secp256r1" => 3,
+        "ristretto" => 4,
+        "bls12_381_1" => 5,
+        _ => 0
+    }
+}
+
+fn decode_point<C: Curve>(bytes: &[u8]) -> Result<Point<C>, VrfError> {
+    let point = Point::<C>::from_bytes(bytes).map_err(|e| match e {
+        PointFromBytesError::DeserializationError => VrfError::InvalidPointEncoding,
+        PointFromBytesError::InvalidPoint(_) => VrfError::NotInSubgroup
+    })?;
+    if &*point.to_bytes(true) != bytes {
+        return Err(VrfError::InvalidPointEncoding);
+    }
+    Ok(point)
+}
+
+// `validate_point`, counting the order check's multiplication when it runs.
+fn validate_point_counted<C: Curve>(p: &Point<C>, ops: &mut OpCounts) -> Result<(), VrfError> {
+    if !p.is_zero() {
+        ops.point_muls += 1;
+    }
+    validate_point(p)
+}
+
+// `y` is a Sha512 digest read as a `BigInt`, which drops leading zeros; this
+// pads it back to 64 bytes without a fallible conversion.
+fn digest_bytes(y: &BigInt) -> [u8; 64] {
+    let bytes = y.to_bytes();
+    let len = bytes.len().min(64);
+    let mut padded = [0u8; 64];
+    padded[64 - len..].copy_from_slice(&bytes[bytes.len() - len..]);
+    padded
+}
+
+fn decode_scalar<C: Curve>(bytes: &[u8]) -> Result<Scalar<C>, VrfError> {
+    let scalar = Scalar::<C>::from_bytes(bytes).map_err(|_| VrfError::NonCanonicalScalar)?;
+    // `from_bytes` may keep an unreduced value that re-encodes as given.
+    let scalar = reduce(&scalar);
+    if &*scalar.to_bytes() != bytes {
+        return Err(VrfError::NonCanonicalScalar);
+    }
+    Ok(scalar)
+}
+
+// `Point<C>` already promises prime order unless built with
+// `from_raw_unchecked`, so the order check is a second line of defence. It is
+// not cheap: checking the order multiplies the point by the group order.
+fn validate_point<C: Curve>(p: &Point<C>) -> Result<(), VrfError> {
+    if p.is_zero() {
+        return Err(VrfError::IdentityPoint);
+    }
+    if !p.as_raw().check_point_order_equals_group_order() {
+        return Err(VrfError::NotInSubgroup);
+    }
+    Ok(())
+}
+
+fn point_hex<C: Curve>(p: &Point<C>) -> String {
+    hex::encode(&*p.to_bytes(true))
+}
+
+fn scalar_hex<C: Curve>(s: &Scalar<C>) -> String {
+    hex::encode(&*s.to_bytes())
+}
+
+/// Domain separation tag for hash-to-curve, following RFC 9380 section 5.3.
+/// Tags must be non-empty; tags longer than 255 bytes are replaced by
+/// `Sha512("H2C-OVERSIZE-DST-" || tag)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dst(Cow<'static, [u8]>);
+
+impl Dst {
+    pub fn new(tag: &[u8]) -> Option<Self> {
+        match tag.len() {
+            0 => None,
+            1..=255 => Some(Self(Cow::Owned(tag.to_vec()))),
+            _ => Some(Self(Cow::Owned(
+                sha2::Sha512::new()
+                    .chain(b"H2C-OVERSIZE-DST-")
+                    .chain(tag)
+                    .finalize()
+                    .to_vec()
+            )))
+        }
+    }
+
+    // Only called to initialize the `const` tags below, so a bad tag fails
+    // the build instead of panicking at run time.
+    const fn from_static(tag: &'static [u8]) -> Self {
+        assert!(!tag.is_empty() && tag.len() <= 255);
+        Self(Cow::Borrowed(tag))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+const G_TILDE_DST: Dst = Dst::from_static(b"rka-vrf-g-tilde");
+const H_TILDE_DST: Dst = Dst::from_static(b"rka-vrf-h-tilde");
+const HASH_POINT_DST: Dst = Dst::from_static(b"rka-vrf-hash-point");
+const OUTPUT_LEN_DST: Dst = Dst::from_static(b"rka-vrf-hash-point-output-len");
+const INPUT_DST: Dst = Dst::from_static(b"rka-vrf-input");
+const POSSESSION_DST: Dst = Dst::from_static(b"rka-vrf-key-possession");
+
+// expand_message_xmd from RFC 9380 section 5.3.1, instantiated with Sha512.
+// The RFC's bound on `LEN` is checked when the length is instantiated.
+fn expand_message_xmd<const LEN: usize>(msg: &[u8], dst: &Dst) -> Vec<u8> {
+    const { assert!(LEN.div_ceil(64) <= 255) };
+    let (len, ell) = (LEN, LEN.div_ceil(64));
+    let dst_prime = [dst.as_bytes(), &[dst.as_bytes().len() as u8]].concat();
+    let b0 = sha2::Sha512::new()
+        .chain([0u8; 128])
+        .chain(msg)
+        .chain((len as u16).to_be_bytes())
+        .chain([0u8])
+        .chain(&dst_prime)
+        .finalize();
+    let mut bi = sha2::Sha512::new()
+        .chain(b0)
+        .chain([1u8])
+        .chain(&dst_prime)
+        .finalize();
+    let mut uniform = bi.to_vec();
+    for i in 2..=ell {
+        let xored: Vec<u8> = b0.iter().zip(bi.iter()).map(|(a, b)| a ^ b).collect();
+        bi = sha2::Sha512::new()
+            .chain(xored)
+            .chain([i as u8])
+            .chain(&dst_prime)
+            .finalize();
+        uniform.extend_from_slice(&bi);
+    }
+    uniform.truncate(len);
+    uniform
+}
+
+// About half the candidates decode, so the loop ends after a couple of
+// rounds; the counter wraps rather than overflowing.
+pub fn hash_to_curve<C: Curve>(dst: &Dst, msg: &[u8]) -> Point<C> {
+    let len = Point::<C>::generator().to_bytes(true).len();
+    let mut ctr = 0u32;
+    loop {
+        let uniform = expand_message_xmd::<64>(&[msg, &ctr.to_be_bytes()].concat(), dst);
+        let candidate = if len == 33 {
+            [&[0x02 | (uniform[32] & 1)], &uniform[..32]].concat()
+        } else {
+            uniform[..len].to_vec()
+        };
+        if let Some(point) = Point::<C>::from_bytes(&candidate).ok().filter(|p| !p.is_zero()) {
+            return point;
+        }
+        ctr = ctr.wrapping_add(1);
+    }
+}
+
+pub struct Generators<C: Curve> {
+    pub g_tilde: Point<C>,
+    pub h_tilde: Point<C>
+}
+
+impl <C: Curve> Generators<C> {
+    pub fn from_seed(seed: &[u8]) -> Self {
+        Self {
+            g_tilde: hash_to_curve(&G_TILDE_DST, seed),
+            h_tilde: hash_to_curve(&H_TILDE_DST, seed)
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct KeyPair<C: Curve> {
+    sk: Scalar<C>,
+    vk: Point<C>
+}
+
+impl <C: Curve> KeyPair<C> {
+    pub fn generate() -> Self {
+        Self::generate_with_rng(&mut OsRng)
+    }
+
+    pub fn generate_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        loop {
+            if let Ok(keypair) = Self::from_secret(random_scalar(rng)) {
+                return keypair;
+            }
+        }
+    }
+
+    pub fn from_secret(sk: Scalar<C>) -> Result<Self, VrfError> {
+        let sk = reduce(&sk);
+        if sk.is_zero() {
+            return Err(VrfError::ZeroSecretKey);
+        }
+        let vk = generator::<C>() * &sk;
+        Ok(Self { sk, vk })
+    }
+
+    pub fn secret_key(&self) -> &Scalar<C> {
+        &self.sk
+    }
+
+    pub fn public_key(&self) -> &Point<C> {
+        &self.vk
+    }
+}
+
+/// Blinds a key additively: `(sk + blind, vk + G * blind)`. Proofs made with
+/// the blinded secret verify under the blinded public key like any other
+/// pair, and reveal nothing linking it to `vk` without `blind`. Pass the
+/// result through `KeyPair::from_secret` to reject the zero key.
+pub fn rerandomize<C: Curve>(sk: &Scalar<C>, blind: &Scalar<C>) -> (Scalar<C>, Point<C>) {
+    let sk = reduce(&(sk + blind));
+    let vk = generator::<C>() * &sk;
+    (sk, vk)
+}
+
+#[derive(Clone, PartialEq)]
 pub struct InversionProof<C: Curve>{
     zt: Scalar<C>,
     zl: Scalar<C>,
@@ -9,7 +334,29 @@ pub struct InversionProof<C: Curve>{
     t1_point: Point<C>
 }
 
+impl <C: Curve> Eq for InversionProof<C> {}
+
+impl <C: Curve> fmt::Debug for InversionProof<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InversionProof")
+            .field("zt", &scalar_hex(&self.zt))
+            .field("zl", &scalar_hex(&self.zl))
+            .field("zr", &scalar_hex(&self.zr))
+            .field("x", &scalar_hex(&self.x))
+            .field("t1_point", &point_hex(&self.t1_point))
+            .finish()
+    }
+}
+
+/// Formats as the lowercase hex of `to_bytes`.
+impl <C: Curve> fmt::Display for InversionProof<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
 impl <C:Curve> InversionProof<C> {
+    #[allow(clippy::too_many_arguments)]
     pub fn challenge(
         g: &Point<C>,
         h: &Point<C>,
@@ -22,7 +369,36 @@ impl <C:Curve> InversionProof<C> {
         t0: &Point<C>,
         t1: &Point<C>
     ) -> Scalar<C> {
-        Scalar::<C>::from_bigint(&sha2::Sha512::new().chain_points([
+        hash_to_scalar(&Self::transcript(g, h, g_tilde, h_tilde, delta, theta, s1, s2, t0, t1))
+    }
+
+    // `statement` is appended as one more length-prefixed element; an empty
+    // one leaves the challenge as `challenge` computes it.
+    fn bound_challenge(transcript: Vec<u8>, statement: &[u8]) -> Scalar<C> {
+        match statement.is_empty() {
+            true => hash_to_scalar(&transcript),
+            false => hash_to_scalar(&TranscriptHasher::with_sink(transcript).append(statement).into_inner())
+        }
+    }
+
+    /// The bytes `challenge` hashes: each point in argument order, compressed
+    /// and prefixed with its length as a little-endian `u64`. The challenge is
+    /// `Sha512` of this string, extended as in `reduce_wide` for groups wider
+    /// than 384 bits, read big-endian and reduced mod the group order.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transcript(
+        g: &Point<C>,
+        h: &Point<C>,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        delta: &Point<C>,
+        theta: &Point<C>,
+        s1: &Point<C>,
+        s2: &Point<C>,
+        t0: &Point<C>,
+        t1: &Point<C>
+    ) -> Vec<u8> {
+        transcript_bytes([
             g,
             h,
             g_tilde,
@@ -33,21 +409,156 @@ impl <C:Curve> InversionProof<C> {
             s2,
             t0,
             t1
-        ]).result_bigint())
+        ])
     }
 
+    /// Panics if `gamma` is zero; see `try_prove`. Not available with the
+    /// `no_panic` feature, nor are `prove_with_rng` and `prove_deterministic`.
+    #[cfg(not(feature = "no_panic"))]
     pub fn prove(g: &Point<C>, h: &Point<C>, g_tilde: &Point<C>, h_tilde: &Point<C>, gamma: &Scalar<C>, delta: &Point<C>, theta: &Point<C>) -> Self {
-        let alpha = Scalar::<C>::random();
-        let beta = Scalar::<C>::random();
+        Self::try_prove(g, h, g_tilde, h_tilde, gamma, delta, theta).unwrap()
+    }
+
+    /// `prove`, failing with `ZeroSecretKey` instead of panicking.
+    pub fn try_prove(g: &Point<C>, h: &Point<C>, g_tilde: &Point<C>, h_tilde: &Point<C>, gamma: &Scalar<C>, delta: &Point<C>, theta: &Point<C>) -> Result<Self, VrfError> {
+        Self::try_prove_with_rng(&mut OsRng, g, h, g_tilde, h_tilde, gamma, delta, theta)
+    }
+
+    #[cfg(not(feature = "no_panic"))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        g: &Point<C>,
+        h: &Point<C>,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        gamma: &Scalar<C>,
+        delta: &Point<C>,
+        theta: &Point<C>
+    ) -> Self {
+        Self::try_prove_with_rng(rng, g, h, g_tilde, h_tilde, gamma, delta, theta).unwrap()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_prove_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        g: &Point<C>,
+        h: &Point<C>,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        gamma: &Scalar<C>,
+        delta: &Point<C>,
+        theta: &Point<C>
+    ) -> Result<Self, VrfError> {
+        Self::prove_bound(rng, g, h, g_tilde, h_tilde, gamma, delta, theta, &[])
+    }
+
+    /// `prove` with `statement` bound into the challenge, so the proof only
+    /// verifies under `verify_bound` with the same statement.
+    #[allow(clippy::too_many_arguments)]
+    fn prove_bound<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        g: &Point<C>,
+        h: &Point<C>,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        gamma: &Scalar<C>,
+        delta: &Point<C>,
+        theta: &Point<C>,
+        statement: &[u8]
+    ) -> Result<Self, VrfError> {
+        let nonces = [(); 4].map(|_| random_scalar::<C, R>(rng));
+        Self::prove_with_nonces(nonces, g, h, g_tilde, h_tilde, gamma, delta, theta, statement)
+    }
+
+    /// Derives the nonces from `gamma` and the statement instead of an RNG, so
+    /// distinct statements always get distinct nonces and a given statement
+    /// always yields the same proof.
+    #[cfg(not(feature = "no_panic"))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_deterministic(
+        g: &Point<C>,
+        h: &Point<C>,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        gamma: &Scalar<C>,
+        delta: &Point<C>,
+        theta: &Point<C>
+    ) -> Self {
+        Self::try_prove_deterministic(g, h, g_tilde, h_tilde, gamma, delta, theta).unwrap()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_prove_deterministic(
+        g: &Point<C>,
+        h: &Point<C>,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        gamma: &Scalar<C>,
+        delta: &Point<C>,
+        theta: &Point<C>
+    ) -> Result<Self, VrfError> {
+        Self::prove_deterministic_bound(g, h, g_tilde, h_tilde, gamma, delta, theta, &[])
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn prove_deterministic_bound(
+        g: &Point<C>,
+        h: &Point<C>,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        gamma: &Scalar<C>,
+        delta: &Point<C>,
+        theta: &Point<C>,
+        statement: &[u8]
+    ) -> Result<Self, VrfError> {
+        let nonces = Self::deterministic_nonces(g, h, g_tilde, h_tilde, gamma, delta, theta, statement);
+        Self::prove_with_nonces(nonces, g, h, g_tilde, h_tilde, gamma, delta, theta, statement)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn deterministic_nonces(
+        g: &Point<C>,
+        h: &Point<C>,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        gamma: &Scalar<C>,
+        delta: &Point<C>,
+        theta: &Point<C>,
+        statement: &[u8]
+    ) -> [Scalar<C>; 4] {
+        let digest = sha2::Sha512::new()
+            .chain(b"rka-vrf-nonce")
+            .chain(&*reduce(gamma).to_bytes())
+            .chain_points([g, h, g_tilde, h_tilde, delta, theta])
+            .chain(statement);
+        let mut i = 0u8;
+        [(); 4].map(|_| {
+            i += 1;
+            Scalar::<C>::from_bigint(&reduce_wide(digest.clone().chain([i]), Scalar::<C>::group_order()))
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn prove_with_nonces(
+        [alpha, beta, tau0, tau1]: [Scalar<C>; 4],
+        g: &Point<C>,
+        h: &Point<C>,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        gamma: &Scalar<C>,
+        delta: &Point<C>,
+        theta: &Point<C>,
+        statement: &[u8]
+    ) -> Result<Self, VrfError> {
+        let gamma_inv = invert_ct(gamma).ok_or(VrfError::ZeroSecretKey)?;
         let s1 = g * &alpha;
         let s2 = h * &beta;
-        let tau0 = Scalar::<C>::random();
-        let tau1 = Scalar::<C>::random();
         let t0 = &alpha * &beta;
-        let t1 = &alpha * &gamma.invert().unwrap() + &beta * gamma;
-        let t0_point = g_tilde * &t0 + h_tilde * &tau0;
+        let t1 = &alpha * &gamma_inv + &beta * gamma;
+        let t0_point = Self::commit_t0(g_tilde, h_tilde, &t0, &tau0);
         let t1_point = g_tilde * &t1 + h_tilde * &tau1;
-        let x = InversionProof::challenge(
+        let transcript = Self::transcript(
             g,
             h,
             g_tilde,
@@ -59,25 +570,115 @@ impl <C:Curve> InversionProof<C> {
             &t0_point,
             &t1_point
         );
+        let x = Self::bound_challenge(transcript, statement);
         let zt = &tau1 * &x + &tau0;
         let zl = &alpha + &x * gamma;
-        let zr = &beta + &x * gamma.invert().unwrap();
-        Self {
+        let zr = &beta + &x * &gamma_inv;
+        Ok(Self {
             zt,
             zl,
             zr,
             x,
             t1_point,
+        })
+    }
+
+    fn commit_t0(g_tilde: &Point<C>, h_tilde: &Point<C>, t0: &Scalar<C>, tau0: &Scalar<C>) -> Point<C> {
+        g_tilde * t0 + h_tilde * tau0
+    }
+
+    // `zl * zr = t0 + t1 * x + x^2` since `gamma * gamma_inv = 1`, and
+    // `zt = tau0 + tau1 * x`, so this is `commit_t0` for an honest proof.
+    fn reconstruct_t0(&self, g_tilde: &Point<C>, h_tilde: &Point<C>) -> Point<C> {
+        g_tilde * (&self.zl * &self.zr - &self.x * &self.x) +
+        h_tilde * (&self.zt) + &self.t1_point * (-&self.x)
+    }
+
+    /// `PROOF_VERSION || curve_id || t1_point || zt || zl || zr || x`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            &[PROOF_VERSION, curve_id::<C>()][..],
+            &*self.t1_point.to_bytes(true),
+            &*self.zt.to_bytes(),
+            &*self.zl.to_bytes(),
+            &*self.zr.to_bytes(),
+            &*self.x.to_bytes()
+        ].concat()
+    }
+
+    /// Length of `to_bytes` on this curve.
+    pub fn serialized_len() -> usize {
+        2 + point_len::<C>() + 4 * scalar_len::<C>()
+    }
+
+    /// Strict inverse of `to_bytes`: non-canonical encodings are rejected.
+    /// Version 1 encodings, `to_bytes` without the version byte, are
+    /// accepted too.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VrfError> {
+        let versioned = match bytes.first() {
+            Some(&PROOF_VERSION) | Some(&PROOF_VERSION_2) => &bytes[1..],
+            Some(&id) if id < 0x80 => bytes,
+            Some(_) => return Err(VrfError::UnsupportedVersion),
+            None => return Err(VrfError::InvalidLength)
+        };
+        match versioned.first() {
+            Some(&id) if id != curve_id::<C>() => return Err(VrfError::WrongCurve),
+            _ if versioned.len() != Self::serialized_len() - 1 => return Err(VrfError::InvalidLength),
+            _ => {}
         }
+        let (t1_point, rest) = versioned[1..].split_at(point_len::<C>());
+        let scalar = |i: usize| decode_scalar::<C>(&rest[i * scalar_len::<C>()..(i + 1) * scalar_len::<C>()]);
+        Ok(Self {
+            zt: scalar(0)?,
+            zl: scalar(1)?,
+            zr: scalar(2)?,
+            x: scalar(3)?,
+            t1_point: decode_point(t1_point)?
+        })
+    }
+
+    /// Lowercase hex of `to_bytes`.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    pub fn from_hex(s: &str) -> Result<Self, VrfError> {
+        Self::from_bytes(&decode_hex(s)?)
     }
 
     pub fn verify(&self, g: &Point<C>, h: &Point<C>, g_tilde: &Point<C>, h_tilde: &Point<C>, delta: &Point<C>, theta: &Point<C>) -> bool {
-        let t0_point = 
-            g_tilde * (&self.zl * &self.zr - &self.x * &self.x) +
-            h_tilde * (&self.zt) + &self.t1_point * (-&self.x);
+        self.verify_bound(g, h, g_tilde, h_tilde, delta, theta, &[])
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn verify_bound(&self, g: &Point<C>, h: &Point<C>, g_tilde: &Point<C>, h_tilde: &Point<C>, delta: &Point<C>, theta: &Point<C>, statement: &[u8]) -> bool {
+        self.verify_counted(g, h, g_tilde, h_tilde, delta, theta, statement, &mut OpCounts::default())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn verify_counted(
+        &self,
+        g: &Point<C>,
+        h: &Point<C>,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        delta: &Point<C>,
+        theta: &Point<C>,
+        statement: &[u8],
+        ops: &mut OpCounts
+    ) -> bool {
+        // A zero challenge drops `delta`, `theta` and `t1_point` from the
+        // equations below, so nothing about the witness would be checked.
+        if self.x.is_zero() || validate_point_counted(&self.t1_point, ops).is_err() {
+            return false;
+        }
+        let t0_point = self.reconstruct_t0(g_tilde, h_tilde);
         let s1 = g * &self.zl + delta * (-&self.x);
         let s2 = h * &self.zr + theta * (-&self.x);
-        let x_comp = InversionProof::challenge(
+        ops.point_muls += 7;
+        ops.point_adds += 4;
+        ops.hashes += 1;
+        let transcript = Self::transcript(
             g,
             h,
             g_tilde,
@@ -89,29 +690,91 @@ impl <C:Curve> InversionProof<C> {
             &t0_point,
             &self.t1_point
         );
-        return x_comp == self.x;
+        Self::bound_challenge(transcript, statement) == self.x
     }
 }
 
+#[derive(Clone, PartialEq)]
 pub struct VRFOutput<C: Curve> {
     y: BigInt,
     u: Point<C>,
-    r: InversionProof<C>
+    r: InversionProof<C>,
+    // Decoded from a version 1 or 2 proof, whose challenge lacks the statement.
+    legacy: bool
+}
+
+impl <C: Curve> Eq for VRFOutput<C> {}
+
+impl <C: Curve> fmt::Debug for VRFOutput<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VRFOutput")
+            .field("y", &self.y.to_hex())
+            .field("u", &point_hex(&self.u))
+            .field("r", &self.r)
+            .finish()
+    }
+}
+
+/// Formats as the lowercase hex of `to_proof_bytes`.
+impl <C: Curve> fmt::Display for VRFOutput<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
 }
 
 impl <C: Curve> VRFOutput<C> {
-    fn hash_point(vk: &Point<C>, x: &Point<C>) -> Point<C> {
-        Point::<C>::generator() * Scalar::<C>::from_bigint(
-            &sha2::Sha512::new().chain_points([
-                vk, x
-            ]).result_bigint()
-        )
+    /// The base point `u` is derived from, `hash_to_curve(vk || x)`. It must
+    /// not be computed as `G * H(vk, x)`: anyone knowing its discrete log to
+    /// `G` could compute `u = vk * (H(vk, x) / sk)` without the secret key.
+    pub fn hash_point(vk: &Point<C>, x: &Point<C>) -> Point<C> {
+        Self::hash_point_with_context(vk, x, &[])
+    }
+
+    /// `hash_to_curve(vk || x || context)`. Point encodings have a fixed width,
+    /// so the context is simply appended; an empty context gives `hash_point`.
+    pub fn hash_point_with_context(vk: &Point<C>, x: &Point<C>, context: &[u8]) -> Point<C> {
+        let msg = [&*vk.to_bytes(true), &*x.to_bytes(true), context].concat();
+        hash_to_curve(&HASH_POINT_DST, &msg)
+    }
+
+    // A separate DST rather than a context, which a caller could choose to
+    // match the encoded `len`.
+    fn hash_point_with_output_len(vk: &Point<C>, x: &Point<C>, len: usize) -> Point<C> {
+        let msg = [&*vk.to_bytes(true), &*x.to_bytes(true), &(len as u64).to_le_bytes()].concat();
+        hash_to_curve(&OUTPUT_LEN_DST, &msg)
+    }
+
+    /// Bound into the challenge of the inversion proof. `vk` and `u` are
+    /// already in its statement and `x` enters through the base point, but
+    /// naming all three ties the proof to this one evaluation.
+    fn statement(vk: &Point<C>, x: &Point<C>, u: &Point<C>) -> Vec<u8> {
+        [&b"rka-vrf-statement"[..], &transcript_bytes([vk, x, u])].concat()
+    }
+
+    // Legacy proofs bind nothing beyond the inversion proof's own points.
+    fn versioned_statement(legacy: bool, vk: &Point<C>, x: &Point<C>, u: &Point<C>) -> Vec<u8> {
+        match legacy {
+            true => Vec::new(),
+            false => Self::statement(vk, x, u)
+        }
+    }
+
+    // `statement` has a fixed length per curve, so appending keeps it
+    // unambiguous; the tag separates empty data from none.
+    fn statement_with_associated_data(vk: &Point<C>, x: &Point<C>, u: &Point<C>, data: &[u8]) -> Vec<u8> {
+        [&Self::statement(vk, x, u), &b"rka-vrf-associated-data"[..], data].concat()
     }
 
     fn hash_output(x: &Point<C>, u: &Point<C>) -> BigInt {
         sha2::Sha512::new().chain_points([x, u]).result_bigint()
     }
 
+    /// Panics if `vk` or `x` is the identity or `sk` is zero; see `try_eval`.
+    /// Not available with the `no_panic` feature.
+    ///
+    /// `sk` is inverted by a fixed sequence of scalar multiplications rather
+    /// than curv's variable-time `invert`, on every evaluation path.
+    #[cfg(not(feature = "no_panic"))]
     pub fn eval(
         g_tilde: &Point<C>,
         h_tilde: &Point<C>,
@@ -119,68 +782,2277 @@ impl <C: Curve> VRFOutput<C> {
         sk: &Scalar<C>,
         x: &Point<C>
     ) -> Self {
-        let base = Self::hash_point(vk, x);
-        let u = &base * &sk.invert().unwrap();
-        let r = InversionProof::prove(
-            &Point::<C>::generator(),
-            &base,
-            g_tilde,
-            h_tilde,
-            &sk,
-            &vk,
-            &u
-        );
-        let y = Self::hash_output(x, &u);
-        Self { y, u, r }
+        Self::try_eval(g_tilde, h_tilde, vk, sk, x).unwrap()
     }
 
-    pub fn verify(
-        &self,
+    pub fn try_eval(
         g_tilde: &Point<C>,
         h_tilde: &Point<C>,
         vk: &Point<C>,
+        sk: &Scalar<C>,
         x: &Point<C>
-    ) -> bool {
-        self.y == Self::hash_output(x, &self.u) && self.r.verify(
-            &Point::<C>::generator(),
-            &Self::hash_point(vk, x),
-            g_tilde,
-            h_tilde,
-            vk,
-            &self.u
-        )
+    ) -> Result<Self, VrfError> {
+        Self::eval_with_rng(&mut OsRng, g_tilde, h_tilde, vk, sk, x)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    // use std::time::{SystemTime, Duration};
-
-    use curv::elliptic::curves::{Scalar, Ed25519, Point, Curve};
+    /// `try_eval` with the proof's nonces drawn from `rng` rather than the OS.
+    pub fn eval_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>
+    ) -> Result<Self, VrfError> {
+        validate_point(vk)?;
+        validate_point(x)?;
+        let base = Self::hash_point(vk, x);
+        Self::eval_prehashed_with(vk, sk, x, &base, |sk, u, statement| {
+            InversionProof::prove_bound(rng, generator::<C>(), &base, g_tilde, h_tilde, sk, vk, u, statement)
+        })
+    }
 
-    use super::{InversionProof, VRFOutput};
+    /// Evaluates under a context string, so independent protocol instances
+    /// sharing a key get unrelated outputs for the same input.
+    pub fn eval_with_context(
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>,
+        context: &[u8]
+    ) -> Result<Self, VrfError> {
+        validate_point(vk)?;
+        validate_point(x)?;
+        let base = Self::hash_point_with_context(vk, x, context);
+        Self::try_eval_prehashed(g_tilde, h_tilde, vk, sk, x, &base)
+    }
 
-    fn test_generic_inversion_proof<C: Curve>() {
+    /// Binds `data` (e.g. a block height) into the proof's challenge only:
+    /// the output is the one `eval` gives, but the proof verifies solely
+    /// under `verify_with_associated_data` with the same `data`.
+    pub fn eval_with_associated_data(
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>,
+        data: &[u8]
+    ) -> Result<Self, VrfError> {
+        Self::eval_with_associated_data_with_rng(&mut OsRng, g_tilde, h_tilde, vk, sk, x, data)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn eval_with_associated_data_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>,
+        data: &[u8]
+    ) -> Result<Self, VrfError> {
+        validate_point(vk)?;
+        validate_point(x)?;
+        let base = Self::hash_point(vk, x);
+        Self::eval_prehashed_with(vk, sk, x, &base, |sk, u, _| {
+            let statement = Self::statement_with_associated_data(vk, x, u, data);
+            InversionProof::prove_bound(rng, generator::<C>(), &base, g_tilde, h_tilde, sk, vk, u, &statement)
+        })
+    }
+
+    /// Evaluates for an output of `len` bytes, returned with the proof.
+    /// `len` is hashed into the base point under its own DST, so the proof
+    /// only verifies under `verify_with_output_len` with the same `len`,
+    /// which is the only other way to read the output.
+    pub fn eval_with_output_len(
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>,
+        len: usize
+    ) -> Result<(Vec<u8>, Self), VrfError> {
+        validate_point(vk)?;
+        validate_point(x)?;
+        let output = Self::try_eval_prehashed(g_tilde, h_tilde, vk, sk, x, &Self::hash_point_with_output_len(vk, x, len))?;
+        Ok((output.output_bytes(len), output))
+    }
+
+    /// `eval` with `base = hash_point(vk, x)` computed by the caller. `x` is
+    /// still needed since the output hashes it. Panics if `sk` is zero; not
+    /// available with the `no_panic` feature.
+    #[cfg(not(feature = "no_panic"))]
+    pub fn eval_prehashed(
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>,
+        base: &Point<C>
+    ) -> Self {
+        Self::try_eval_prehashed(g_tilde, h_tilde, vk, sk, x, base).unwrap()
+    }
+
+    /// `eval_prehashed`, failing with `ZeroSecretKey` instead of panicking.
+    pub fn try_eval_prehashed(
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>,
+        base: &Point<C>
+    ) -> Result<Self, VrfError> {
+        Self::eval_prehashed_with_rng(&mut OsRng, g_tilde, h_tilde, vk, sk, x, base)
+    }
+
+    /// `try_eval_prehashed` with the proof's nonces drawn from `rng`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn eval_prehashed_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>,
+        base: &Point<C>
+    ) -> Result<Self, VrfError> {
+        Self::eval_prehashed_with(vk, sk, x, base, |sk, u, statement| {
+            InversionProof::prove_bound(rng, generator::<C>(), base, g_tilde, h_tilde, sk, vk, u, statement)
+        })
+    }
+
+    /// `eval` with the inversion proof made by `InversionProof::prove_deterministic`,
+    /// so the whole output is a function of the key, the input and the
+    /// generators, e.g. for test vectors.
+    pub fn eval_deterministic(
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>
+    ) -> Result<Self, VrfError> {
+        validate_point(vk)?;
+        validate_point(x)?;
+        let base = Self::hash_point(vk, x);
+        Self::eval_prehashed_with(vk, sk, x, &base, |sk, u, statement| {
+            InversionProof::prove_deterministic_bound(generator::<C>(), &base, g_tilde, h_tilde, sk, vk, u, statement)
+        })
+    }
+
+    // `prove` is handed the reduced key, `u` and the statement to bind, and
+    // proves `vk = G * sk`, `u = base / sk`.
+    fn eval_prehashed_with(
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>,
+        base: &Point<C>,
+        prove: impl FnOnce(&Scalar<C>, &Point<C>, &[u8]) -> Result<InversionProof<C>, VrfError>
+    ) -> Result<Self, VrfError> {
+        let sk = &reduce(sk);
+        let u = base * &invert_ct(sk).ok_or(VrfError::ZeroSecretKey)?;
+        let r = prove(sk, &u, &Self::statement(vk, x, &u))?;
+        let y = Self::hash_output(x, &u);
+        Ok(Self { y, u, r, legacy: false })
+    }
+
+    /// Panics if `sk` is zero; see `try_eval_encrypted`. Not available with
+    /// the `no_panic` feature, nor is `eval_encrypted_with_rng`.
+    #[cfg(not(feature = "no_panic"))]
+    pub fn eval_encrypted(recipient_pk: &Point<C>, vk: &Point<C>, sk: &Scalar<C>, x: &Point<C>) -> EncryptedOutput<C> {
+        Self::try_eval_encrypted(recipient_pk, vk, sk, x).unwrap()
+    }
+
+    pub fn try_eval_encrypted(recipient_pk: &Point<C>, vk: &Point<C>, sk: &Scalar<C>, x: &Point<C>) -> Result<EncryptedOutput<C>, VrfError> {
+        Self::try_eval_encrypted_with_rng(&mut OsRng, recipient_pk, vk, sk, x)
+    }
+
+    #[cfg(not(feature = "no_panic"))]
+    pub fn eval_encrypted_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        recipient_pk: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>
+    ) -> EncryptedOutput<C> {
+        Self::try_eval_encrypted_with_rng(rng, recipient_pk, vk, sk, x).unwrap()
+    }
+
+    pub fn try_eval_encrypted_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        recipient_pk: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>
+    ) -> Result<EncryptedOutput<C>, VrfError> {
+        // An identity or small-order `recipient_pk` would leave `u` readable
+        // from `c2`, so it is rejected before anything depends on `sk`.
+        validate_point(recipient_pk)?;
+        validate_point(vk)?;
+        validate_point(x)?;
+        let g = generator::<C>();
+        let sk = &reduce(sk);
+        let base = Self::hash_point(vk, x);
+        let u = &base * &invert_ct(sk).ok_or(VrfError::ZeroSecretKey)?;
+        let r = random_scalar::<C, _>(rng);
+        let w = &r * sk;
+        let c1 = g * &r;
+        let c2 = u + recipient_pk * &r;
+
+        let (k_sk, k_r, k_w) = (random_scalar::<C, _>(rng), random_scalar::<C, _>(rng), random_scalar::<C, _>(rng));
+        let a1 = g * &k_sk;
+        let a2 = g * &k_r;
+        let a3 = &c2 * &k_sk - recipient_pk * &k_w;
+        let a4 = &c1 * &k_sk - g * &k_w;
+        let c = EncryptedOutput::challenge(recipient_pk, vk, &base, &c1, &c2, &a1, &a2, &a3, &a4);
+        Ok(EncryptedOutput {
+            z_sk: k_sk + &c * sk,
+            z_r: k_r + &c * &r,
+            z_w: k_w + &c * &w,
+            c1,
+            c2,
+            c
+        })
+    }
+
+    #[cfg(not(feature = "no_panic"))]
+    pub fn eval_bytes(keypair: &KeyPair<C>, params: &Generators<C>, msg: &[u8]) -> Self {
+        Self::eval(
+            &params.g_tilde,
+            &params.h_tilde,
+            keypair.public_key(),
+            keypair.secret_key(),
+            &input_point(msg)
+        )
+    }
+
+    pub fn verify_bytes(&self, params: &Generators<C>, vk: &Point<C>, msg: &[u8]) -> bool {
+        self.verify(&params.g_tilde, &params.h_tilde, vk, &input_point(msg))
+    }
+
+    pub fn verify(
+        &self,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>
+    ) -> bool {
+        self.verify_counted(g_tilde, h_tilde, vk, x, &mut OpCounts::default())
+    }
+
+    /// Rejects proofs from revoked keys before doing any group operations.
+    pub fn verify_not_revoked(
+        &self,
+        vk: &Point<C>,
+        x: &Point<C>,
+        params: &Generators<C>,
+        revoked: &BloomFilter
+    ) -> VerifyOutcome {
+        if revoked.contains(&vk.to_bytes(true)) {
+            return VerifyOutcome::Revoked;
+        }
+        match self.verify(&params.g_tilde, &params.h_tilde, vk, x) {
+            true => VerifyOutcome::Valid,
+            false => VerifyOutcome::Invalid
+        }
+    }
+
+    /// `u || InversionProof::to_bytes`. The output `y` is left out as it
+    /// is recomputed from `u` and the input. A proof decoded from version 1
+    /// or 2 is written back as version 2, keeping its unbound challenge.
+    pub fn to_proof_bytes(&self) -> Vec<u8> {
+        let mut r = self.r.to_bytes();
+        if self.legacy {
+            r[0] = PROOF_VERSION_2;
+        }
+        [&*self.u.to_bytes(true), &r].concat()
+    }
+
+    /// Decodes `proof_bytes` strictly and verifies the result. Any encoding
+    /// other than the one `to_proof_bytes` produces is rejected, even if it
+    /// would decode to the same point or scalar.
+    pub fn verify_canonical(
+        proof_bytes: &[u8],
+        vk: &Point<C>,
+        x: &Point<C>,
+        params: &Generators<C>
+    ) -> Result<(), VrfError> {
+        let output = Self::from_proof_bytes(proof_bytes, x)?;
+        output.verify_with_metadata(&params.g_tilde, &params.h_tilde, vk, x).map(|_| ())
+    }
+
+    /// Strict inverse of `to_proof_bytes`, recomputing `y` from `x`. The
+    /// result is unverified. Proofs from before `PROOF_VERSION` 3 decode too
+    /// and are verified against the challenge they were made with.
+    pub fn from_proof_bytes(proof_bytes: &[u8], x: &Point<C>) -> Result<Self, VrfError> {
+        let proof_len = proof_bytes.len().checked_sub(point_len::<C>());
+        let current = InversionProof::<C>::serialized_len();
+        if proof_len != Some(current) && proof_len != Some(current - 1) {
+            return Err(VrfError::InvalidLength);
+        }
+        let (u, r) = proof_bytes.split_at(point_len::<C>());
+        let u = decode_point::<C>(u)?;
+        Ok(VRFOutput {
+            y: Self::hash_output(x, &u),
+            u,
+            r: InversionProof::from_bytes(r)?,
+            legacy: r[0] != PROOF_VERSION
+        })
+    }
+
+    /// Lowercase hex of `to_proof_bytes`.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_proof_bytes())
+    }
+
+    /// `from_proof_bytes` on hex input. The result is unverified.
+    pub fn from_hex(s: &str, x: &Point<C>) -> Result<Self, VrfError> {
+        Self::from_proof_bytes(&decode_hex(s)?, x)
+    }
+
+    /// `n` independent values derived from `u`, e.g. for sortition. Only
+    /// meaningful once `verify` has succeeded.
+    pub fn expand(&self, n: usize) -> Vec<[u8; 64]> {
+        crate::output::expand(&self.u.to_bytes(true), n)
+    }
+
+    pub fn output_value(&self) -> &BigInt {
+        &self.y
+    }
+
+    /// The output in the crate-wide 64-byte format shared with `ec_vrf`.
+    /// The two schemes are different functions, so their normalized outputs
+    /// are never expected to coincide, even on related inputs.
+    pub fn normalize_output(&self) -> [u8; 64] {
+        crate::output::normalize(&digest_bytes(&self.y))
+    }
+
+    /// Returns the output after only recomputing the output hash, deferring
+    /// the inversion proof check to `VerificationHandle::finalize`.
+    ///
+    /// The returned output is unauthenticated: it must not be acted upon
+    /// until `finalize()` has returned `true`.
+    pub fn verify_lazy(
+        &self,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>
+    ) -> (VrfOutputHash, VerificationHandle<'_, C>) {
+        let points_valid = validate_point(vk).is_ok() && validate_point(x).is_ok();
+        let handle = VerificationHandle {
+            output: self,
+            g_tilde: g_tilde.clone(),
+            h_tilde: h_tilde.clone(),
+            vk: vk.clone(),
+            x: x.clone(),
+            output_matches: points_valid && self.y == Self::hash_output(x, &self.u)
+        };
+        (self.y.clone(), handle)
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn verify_with_metrics(
+        &self,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>
+    ) -> (bool, OpCounts) {
+        let mut ops = OpCounts::default();
+        let valid = self.verify_counted(g_tilde, h_tilde, vk, x, &mut ops);
+        (valid, ops)
+    }
+
+    fn verify_counted(
+        &self,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>,
+        ops: &mut OpCounts
+    ) -> bool {
+        Self::verify_parts(&self.y, &self.u, &self.r, self.legacy, g_tilde, h_tilde, vk, x, ops)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn verify_parts(
+        y: &BigInt,
+        u: &Point<C>,
+        r: &InversionProof<C>,
+        legacy: bool,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>,
+        ops: &mut OpCounts
+    ) -> bool {
+        validate_point_counted(vk, ops).is_ok()
+            && validate_point_counted(x, ops).is_ok()
+            && Self::verify_validated_parts(y, u, r, legacy, g_tilde, h_tilde, vk, x, ops)
+    }
+
+    // `verify_parts` once `vk` and `x` are known to be valid.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_validated_parts(
+        y: &BigInt,
+        u: &Point<C>,
+        r: &InversionProof<C>,
+        legacy: bool,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>,
+        ops: &mut OpCounts
+    ) -> bool {
+        ops.hashes += 1;
+        if *y != Self::hash_output(x, u) {
+            return false;
+        }
+        ops.hashes += 1;
+        r.verify_counted(
+            generator::<C>(),
+            &Self::hash_point(vk, x),
+            g_tilde,
+            h_tilde,
+            vk,
+            u,
+            &Self::versioned_statement(legacy, vk, x, u),
+            ops
+        )
+    }
+
+    /// `eval`, with the output and the proof of its correctness returned
+    /// separately, e.g. to publish the output and hand out the proof on
+    /// demand. Check them with `verify_split`.
+    #[cfg(not(feature = "no_panic"))]
+    pub fn eval_split(
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        sk: &Scalar<C>,
+        x: &Point<C>
+    ) -> (Output<C>, InversionProof<C>) {
+        let VRFOutput { y, u, r, .. } = Self::eval(g_tilde, h_tilde, vk, sk, x);
+        (Output { y, u }, r)
+    }
+
+    pub fn verify_split(
+        output: &Output<C>,
+        proof: &InversionProof<C>,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>
+    ) -> bool {
+        Self::verify_parts(&output.y, &output.u, proof, false, g_tilde, h_tilde, vk, x, &mut OpCounts::default())
+    }
+
+    pub fn verify_with_context(
+        &self,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>,
+        context: &[u8]
+    ) -> bool {
+        self.verify_prehashed(g_tilde, h_tilde, vk, x, &Self::hash_point_with_context(vk, x, context))
+    }
+
+    /// The `len`-byte output, if the proof verifies for `len`.
+    pub fn verify_with_output_len(
+        &self,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>,
+        len: usize
+    ) -> Option<Vec<u8>> {
+        self.verify_prehashed(g_tilde, h_tilde, vk, x, &Self::hash_point_with_output_len(vk, x, len))
+            .then(|| self.output_bytes(len))
+    }
+
+    // `len` bytes of SHAKE256 over `len` and `u`, so outputs of different
+    // lengths are unrelated rather than prefixes of each other. `y` stays the
+    // Sha512 `hash_output`, which stored outputs and test vectors pin.
+    fn output_bytes(&self, len: usize) -> Vec<u8> {
+        use sha3::{digest::{ExtendableOutput, Update, XofReader}, Shake256};
+
+        let mut reader = Shake256::default()
+            .chain(b"rka-vrf-output")
+            .chain((len as u64).to_le_bytes())
+            .chain(&*self.u.to_bytes(true))
+            .finalize_xof();
+        let mut output = vec![0u8; len];
+        reader.read(&mut output);
+        output
+    }
+
+    /// `verify` with `base = hash_point(vk, x)` computed by the caller.
+    pub fn verify_prehashed(
+        &self,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>,
+        base: &Point<C>
+    ) -> bool {
+        validate_point(vk).is_ok()
+            && validate_point(x).is_ok()
+            && self.y == Self::hash_output(x, &self.u)
+            && self.r.verify_bound(generator::<C>(), base, g_tilde, h_tilde, vk, &self.u, &Self::versioned_statement(self.legacy, vk, x, &self.u))
+    }
+
+    pub fn verify_with_associated_data(
+        &self,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>,
+        data: &[u8]
+    ) -> bool {
+        validate_point(vk).is_ok()
+            && validate_point(x).is_ok()
+            && self.y == Self::hash_output(x, &self.u)
+            && self.r.verify_bound(
+                generator::<C>(),
+                &Self::hash_point(vk, x),
+                g_tilde,
+                h_tilde,
+                vk,
+                &self.u,
+                &Self::statement_with_associated_data(vk, x, &self.u, data)
+            )
+    }
+
+    /// Checks only the inversion proof, skipping the comparison of `y` with
+    /// the hash of `x` and `u`. This does NOT bind the output: a valid proof
+    /// passes with any `y`, so use it only when `y` is trusted from elsewhere.
+    pub fn verify_proof_only(
+        &self,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>
+    ) -> bool {
+        validate_point(vk).is_ok()
+            && validate_point(x).is_ok()
+            && self.r.verify_bound(generator::<C>(), &Self::hash_point(vk, x), g_tilde, h_tilde, vk, &self.u, &Self::versioned_statement(self.legacy, vk, x, &self.u))
+    }
+
+    /// Runs every check `verify` does, without stopping at the first failure,
+    /// and reports all that failed in order: `vk`, `x`, the output hash
+    /// (`OutputMismatch`), then the inversion proof (`ProofRejected`).
+    pub fn verify_detailed(
+        &self,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>
+    ) -> Result<(), Vec<VrfError>> {
+        let mut errors: Vec<_> = [validate_point(vk), validate_point(x)].into_iter().filter_map(Result::err).collect();
+        if self.y != Self::hash_output(x, &self.u) {
+            errors.push(VrfError::OutputMismatch);
+        }
+        let statement = Self::versioned_statement(self.legacy, vk, x, &self.u);
+        if !self.r.verify_bound(generator::<C>(), &Self::hash_point(vk, x), g_tilde, h_tilde, vk, &self.u, &statement) {
+            errors.push(VrfError::ProofRejected);
+        }
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors)
+        }
+    }
+
+    pub fn verify_with_metadata(
+        &self,
+        g_tilde: &Point<C>,
+        h_tilde: &Point<C>,
+        vk: &Point<C>,
+        x: &Point<C>
+    ) -> Result<ProofMetadata, VrfError> {
+        validate_point(vk)?;
+        validate_point(x)?;
+        let mut ops = OpCounts::default();
+        if !Self::verify_validated_parts(&self.y, &self.u, &self.r, self.legacy, g_tilde, h_tilde, vk, x, &mut ops) {
+            return Err(VrfError::ProofRejected);
+        }
+        Ok(ProofMetadata {
+            curve: C::CURVE_NAME,
+            suite: "rka-vrf",
+            output_len: sha2::Sha512::output_size(),
+            proof_len: point_len::<C>() + InversionProof::<C>::serialized_len()
+        })
+    }
+}
+
+impl VRFOutput<Secp256k1> {
+    /// Layout for EVM verifiers, in 32-byte words where possible: `u` and
+    /// `t1_point` as 65-byte uncompressed points (`0x04 || X || Y`), `y` as
+    /// 64 bytes big-endian (it is a full Sha512 digest, so two words), and
+    /// the scalars as 32 bytes big-endian:
+    /// `u || y || t1_point || zt || zl || zr || x`, 322 bytes.
+    pub fn to_eth_bytes(&self) -> Vec<u8> {
+        [
+            &*self.u.to_bytes(false),
+            &digest_bytes(&self.y),
+            &*self.r.t1_point.to_bytes(false),
+            &*self.r.zt.to_bytes(),
+            &*self.r.zl.to_bytes(),
+            &*self.r.zr.to_bytes(),
+            &*self.r.x.to_bytes()
+        ].concat()
+    }
+}
+
+/// What `verify_with_metadata` reports about a proof that verified, here and
+/// in `ec_vrf`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofMetadata {
+    /// Name of the curve the proof is over, e.g. `"ed25519"`.
+    pub curve: &'static str,
+    /// Which scheme made the proof: `"rka-vrf"` or `"ec-vrf"`.
+    pub suite: &'static str,
+    /// Length in bytes of the VRF output `y`.
+    pub output_len: usize,
+    /// Length in bytes of the proof's `to_proof_bytes` encoding.
+    pub proof_len: usize,
+}
+
+/// The output half of a `VRFOutput`: `y`, and the point `u` it is hashed
+/// from, which the `InversionProof` is about.
+#[derive(Clone, PartialEq)]
+pub struct Output<C: Curve> {
+    y: BigInt,
+    u: Point<C>
+}
+
+impl <C: Curve> Output<C> {
+    pub fn value(&self) -> &BigInt {
+        &self.y
+    }
+}
+
+impl <C: Curve> fmt::Debug for Output<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Output")
+            .field("y", &self.y.to_hex())
+            .field("u", &point_hex(&self.u))
+            .finish()
+    }
+}
+
+pub type VrfOutputHash = BigInt;
+
+/// Deferred half of `VRFOutput::verify_lazy`.
+pub struct VerificationHandle<'a, C: Curve> {
+    output: &'a VRFOutput<C>,
+    g_tilde: Point<C>,
+    h_tilde: Point<C>,
+    vk: Point<C>,
+    x: Point<C>,
+    output_matches: bool
+}
+
+impl <'a, C: Curve> VerificationHandle<'a, C> {
+    pub fn finalize(self) -> bool {
+        self.output_matches && self.output.r.verify_bound(
+            generator::<C>(),
+            &VRFOutput::hash_point(&self.vk, &self.x),
+            &self.g_tilde,
+            &self.h_tilde,
+            &self.vk,
+            &self.output.u,
+            &VRFOutput::versioned_statement(self.output.legacy, &self.vk, &self.x, &self.output.u)
+        )
+    }
+}
+
+// Only exists to implement `Vrf` over the panicking `eval_bytes`.
+#[cfg(not(feature = "no_panic"))]
+pub struct RkaVrf<C: Curve> {
+    params: Generators<C>
+}
+
+#[cfg(not(feature = "no_panic"))]
+impl <C: Curve> RkaVrf<C> {
+    pub fn new(params: Generators<C>) -> Self {
+        Self { params }
+    }
+}
+
+#[cfg(not(feature = "no_panic"))]
+impl <C: Curve> Vrf for RkaVrf<C> {
+    type PublicKey = Point<C>;
+    type SecretKey = KeyPair<C>;
+    type Output = BigInt;
+    type Proof = VRFOutput<C>;
+
+    fn keygen(&self) -> (KeyPair<C>, Point<C>) {
+        let keypair = KeyPair::generate();
+        let vk = keypair.public_key().clone();
+        (keypair, vk)
+    }
+
+    fn eval(&self, sk: &KeyPair<C>, msg: &[u8]) -> (BigInt, VRFOutput<C>) {
+        let output = VRFOutput::eval_bytes(sk, &self.params, msg);
+        (output.y.clone(), output)
+    }
+
+    fn verify(&self, vk: &Point<C>, msg: &[u8], proof: &VRFOutput<C>) -> bool {
+        proof.verify_bytes(&self.params, vk, msg)
+    }
+}
+
+fn input_point<C: Curve>(msg: &[u8]) -> Point<C> {
+    hash_to_curve(&INPUT_DST, msg)
+}
+
+/// A VRF input `x` hashed from an application message, as `eval_bytes` does.
+/// Pass `input.as_point()` wherever `eval` and `verify` take `x: &Point<C>`,
+/// or convert it into the point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VrfInput<C: Curve>(Point<C>);
+
+impl <C: Curve> VrfInput<C> {
+    pub fn from_message(msg: &[u8]) -> Self {
+        Self(input_point(msg))
+    }
+
+    pub fn as_point(&self) -> &Point<C> {
+        &self.0
+    }
+}
+
+impl <C: Curve> From<VrfInput<C>> for Point<C> {
+    fn from(input: VrfInput<C>) -> Point<C> {
+        input.0
+    }
+}
+
+/// Curve points of a fixed set of inputs, hashed once up front so repeated
+/// evaluations over the same candidates skip hash-to-curve.
+pub struct InputTable<C: Curve> {
+    points: Vec<Point<C>>
+}
+
+impl <C: Curve> InputTable<C> {
+    pub fn new(inputs: &[&[u8]]) -> Self {
+        Self { points: inputs.iter().map(|msg| input_point(msg)).collect() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn point(&self, index: usize) -> Option<&Point<C>> {
+        self.points.get(index)
+    }
+
+    pub fn eval_indexed(&self, keypair: &KeyPair<C>, params: &Generators<C>, index: usize) -> Option<VRFOutput<C>> {
+        let x = self.points.get(index)?;
+        VRFOutput::try_eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), x).ok()
+    }
+}
+
+/// Proofs stored field by field: all `y`s together, then all `u`s, and so on,
+/// which compresses far better than interleaved records.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ColumnarBlob {
+    pub y: Vec<u8>,
+    pub u: Vec<u8>,
+    pub t1_point: Vec<u8>,
+    pub zt: Vec<u8>,
+    pub zl: Vec<u8>,
+    pub zr: Vec<u8>,
+    pub x: Vec<u8>
+}
+
+pub fn serialize_columnar<C: Curve>(outputs: &[VRFOutput<C>]) -> ColumnarBlob {
+    let mut blob = ColumnarBlob::default();
+    for output in outputs {
+        blob.y.extend_from_slice(&digest_bytes(&output.y));
+        blob.u.extend_from_slice(&output.u.to_bytes(true));
+        blob.t1_point.extend_from_slice(&output.r.t1_point.to_bytes(true));
+        blob.zt.extend_from_slice(&output.r.zt.to_bytes());
+        blob.zl.extend_from_slice(&output.r.zl.to_bytes());
+        blob.zr.extend_from_slice(&output.r.zr.to_bytes());
+        blob.x.extend_from_slice(&output.r.x.to_bytes());
+    }
+    blob
+}
+
+pub fn deserialize_columnar<C: Curve>(blob: &ColumnarBlob) -> Result<Vec<VRFOutput<C>>, VrfError> {
+    let (point_len, scalar_len) = (point_len::<C>(), scalar_len::<C>());
+    let n = blob.y.len() / 64;
+    if blob.y.len() != n * 64
+        || blob.u.len() != n * point_len
+        || blob.t1_point.len() != n * point_len
+        || [&blob.zt, &blob.zl, &blob.zr, &blob.x].iter().any(|c| c.len() != n * scalar_len)
+    {
+        return Err(VrfError::InvalidLength);
+    }
+    (0..n).map(|i| {
+        let point = |column: &[u8]| decode_point::<C>(&column[i * point_len..(i + 1) * point_len]);
+        let scalar = |column: &[u8]| decode_scalar::<C>(&column[i * scalar_len..(i + 1) * scalar_len]);
+        Ok(VRFOutput {
+            y: BigInt::from_bytes(&blob.y[i * 64..(i + 1) * 64]),
+            u: point(&blob.u)?,
+            r: InversionProof {
+                zt: scalar(&blob.zt)?,
+                zl: scalar(&blob.zl)?,
+                zr: scalar(&blob.zr)?,
+                x: scalar(&blob.x)?,
+                t1_point: point(&blob.t1_point)?
+            },
+            legacy: false
+        })
+    }).collect()
+}
+
+/// ElGamal encryption `(c1, c2) = (r * G, u + r * recipient_pk)` of the VRF
+/// point `u`, so only the recipient learns the output, together with a proof
+/// that it decrypts to `hash_point(vk, x) * sk^-1` for the `sk` behind `vk`.
+///
+/// With `w = r * sk` the proof shows knowledge of `(sk, r, w)` such that
+/// `vk = sk * G`, `c1 = r * G`, `0 = sk * c1 - w * G` and
+/// `base = sk * c2 - w * recipient_pk`; the last two force
+/// `c2 - r * recipient_pk = base * sk^-1`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncryptedOutput<C: Curve> {
+    c1: Point<C>,
+    c2: Point<C>,
+    c: Scalar<C>,
+    z_sk: Scalar<C>,
+    z_r: Scalar<C>,
+    z_w: Scalar<C>
+}
+
+impl <C: Curve> EncryptedOutput<C> {
+    #[allow(clippy::too_many_arguments)]
+    fn challenge(
+        recipient_pk: &Point<C>,
+        vk: &Point<C>,
+        base: &Point<C>,
+        c1: &Point<C>,
+        c2: &Point<C>,
+        a1: &Point<C>,
+        a2: &Point<C>,
+        a3: &Point<C>,
+        a4: &Point<C>
+    ) -> Scalar<C> {
+        hash_to_scalar(&transcript_bytes([
+            recipient_pk,
+            vk,
+            base,
+            c1,
+            c2,
+            a1,
+            a2,
+            a3,
+            a4
+        ]))
+    }
+
+    pub fn verify(&self, recipient_pk: &Point<C>, vk: &Point<C>, x: &Point<C>) -> bool {
+        if validate_point(vk).is_err() || validate_point(x).is_err() {
+            return false;
+        }
+        let g = generator::<C>();
+        let base = VRFOutput::hash_point(vk, x);
+        let a1 = g * &self.z_sk - vk * &self.c;
+        let a2 = g * &self.z_r - &self.c1 * &self.c;
+        let a3 = &self.c2 * &self.z_sk - recipient_pk * &self.z_w - &base * &self.c;
+        let a4 = &self.c1 * &self.z_sk - g * &self.z_w;
+        self.c == Self::challenge(recipient_pk, vk, &base, &self.c1, &self.c2, &a1, &a2, &a3, &a4)
+    }
+
+    /// Recovers the output `y`. Only meaningful once `verify` has succeeded.
+    pub fn decrypt(&self, recipient_sk: &Scalar<C>, x: &Point<C>) -> BigInt {
+        let u = &self.c2 - &self.c1 * recipient_sk;
+        VRFOutput::hash_output(x, &u)
+    }
+}
+
+/// The output with the smallest `y`, i.e. the leader election winner. Outputs
+/// must be verified before they are compared.
+pub fn select_min<C: Curve>(outputs: &[VRFOutput<C>]) -> Option<&VRFOutput<C>> {
+    outputs.iter().min_by(|a, b| a.y.cmp(&b.y))
+}
+
+/// Beacon value combining every contributor's output: the `y` values sorted,
+/// so the result does not depend on the order of `outputs`, then hashed
+/// together. Outputs must be verified before they are combined.
+pub fn combine_outputs<C: Curve>(outputs: &[&VRFOutput<C>]) -> [u8; 64] {
+    let mut ys: Vec<_> = outputs.iter().map(|output| digest_bytes(&output.y)).collect();
+    ys.sort_unstable();
+    ys.iter().fold(TranscriptHasher::new().append(b"rka-vrf-combine"), |t, y| t.append(y)).finalize()
+}
+
+/// Compressed encoding of a point, as used to identify registered keys.
+pub type PointBytes = Vec<u8>;
+
+/// Indices `(i, j)`, `i < j`, of the first two outputs sharing the point `u`,
+/// ordered by `j`. For a fixed input, equal `u` means the same key signed
+/// twice, e.g. a participant equivocating in a beacon round.
+pub fn find_duplicate_gammas<C: Curve>(outputs: &[VRFOutput<C>]) -> Option<(usize, usize)> {
+    let mut seen: HashMap<PointBytes, usize> = HashMap::new();
+    for (j, output) in outputs.iter().enumerate() {
+        if let Some(i) = seen.insert(output.u.to_bytes(true).to_vec(), j) {
+            return Some((i, j));
+        }
+    }
+    None
+}
+
+/// Checks that `proofs` holds exactly one valid proof on `x` from every
+/// registered key.
+pub fn verify_epoch<C: Curve>(
+    proofs: &[(VRFOutput<C>, Point<C>)],
+    registered_keys: &HashSet<PointBytes>,
+    x: &Point<C>,
+    params: &Generators<C>
+) -> Result<(), EpochError> {
+    let mut seen = HashSet::new();
+    for (i, (output, vk)) in proofs.iter().enumerate() {
+        let key = vk.to_bytes(true).to_vec();
+        if !registered_keys.contains(&key) {
+            return Err(EpochError::UnregisteredKey(i));
+        }
+        if !seen.insert(key) {
+            return Err(EpochError::DuplicateKey(i));
+        }
+        if !output.verify(&params.g_tilde, &params.h_tilde, vk, x) {
+            return Err(EpochError::InvalidProof(i));
+        }
+    }
+    match registered_keys.len() - seen.len() {
+        0 => Ok(()),
+        missing => Err(EpochError::MissingKeys(missing))
+    }
+}
+
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 4096;
+
+/// Verifies many `(output, vk, x)` proofs under shared generators. Batches
+/// larger than `max_batch_size` are rejected up front, so untrusted input
+/// cannot force unbounded work.
+pub struct BatchVerify<'a, C: Curve> {
+    params: &'a Generators<C>,
+    max_batch_size: usize
+}
+
+impl <'a, C: Curve> BatchVerify<'a, C> {
+    pub fn new(params: &'a Generators<C>) -> Self {
+        Self { params, max_batch_size: DEFAULT_MAX_BATCH_SIZE }
+    }
+
+    pub fn max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
+    /// `Ok(true)` only if every proof in the batch verifies.
+    ///
+    /// This is CPU-bound and can take a while on large batches; from async
+    /// code, run it on a blocking thread (e.g. tokio's `spawn_blocking`).
+    pub fn verify(&self, batch: &[(&VRFOutput<C>, &Point<C>, &Point<C>)]) -> Result<bool, VrfError> {
+        self.verify_counted(batch, &mut OpCounts::default())
+    }
+
+    /// `verify` with the proofs checked in parallel on the rayon thread pool.
+    /// Accepts exactly the batches `verify` accepts. Still blocking, so the
+    /// same advice about async callers applies.
+    #[cfg(feature = "rayon")]
+    pub fn verify_parallel(&self, batch: &[(&VRFOutput<C>, &Point<C>, &Point<C>)]) -> Result<bool, VrfError>
+    where
+        Point<C>: Sync,
+        Scalar<C>: Sync
+    {
+        use rayon::prelude::*;
+
+        if batch.len() > self.max_batch_size {
+            return Err(VrfError::BatchTooLarge);
+        }
+        Ok(batch.par_iter().all(|(output, vk, x)| output.verify(&self.params.g_tilde, &self.params.h_tilde, vk, x)))
+    }
+
+    fn verify_counted(&self, batch: &[(&VRFOutput<C>, &Point<C>, &Point<C>)], ops: &mut OpCounts) -> Result<bool, VrfError> {
+        if batch.len() > self.max_batch_size {
+            return Err(VrfError::BatchTooLarge);
+        }
+        Ok(batch.iter().all(|(output, vk, x)| {
+            output.verify_counted(&self.params.g_tilde, &self.params.h_tilde, vk, x, ops)
+        }))
+    }
+}
+
+// Derived from the key alone, so a possession proof for one key never
+// verifies for another.
+fn possession_input<C: Curve>(vk: &Point<C>) -> Point<C> {
+    hash_to_curve(&POSSESSION_DST, &vk.to_bytes(true))
+}
+
+/// Evaluates on an input derived from the public key, proving that the
+/// holder knows the secret key. `aggregate_keys` takes one per member.
+pub fn prove_possession<C: Curve>(keypair: &KeyPair<C>, params: &Generators<C>) -> Result<VRFOutput<C>, VrfError> {
+    VRFOutput::try_eval(
+        &params.g_tilde,
+        &params.h_tilde,
+        keypair.public_key(),
+        keypair.secret_key(),
+        &possession_input(keypair.public_key())
+    )
+}
+
+/// The committee key `vk_0 + vk_1 + ...`, for the secret
+/// `sk_0 + sk_1 + ...`, from each member's key and `prove_possession` output.
+///
+/// Fails with `ProofRejected` unless every possession proof verifies: a
+/// member who could not prove possession could have chosen `vk_i` to cancel
+/// the others' keys and control the aggregate alone.
+pub fn aggregate_keys<C: Curve>(
+    members: &[(Point<C>, VRFOutput<C>)],
+    params: &Generators<C>
+) -> Result<Point<C>, VrfError> {
+    let proven = members.iter().all(|(vk, proof)| {
+        proof.verify(&params.g_tilde, &params.h_tilde, vk, &possession_input(vk))
+    });
+    if !proven {
+        return Err(VrfError::ProofRejected);
+    }
+    let vk = members.iter().map(|(vk, _)| vk).sum();
+    validate_point(&vk)?;
+    Ok(vk)
+}
+
+/// Verifies `output` against `aggregate_keys(members, params)`, rejecting it
+/// if any member's possession proof fails.
+pub fn verify_aggregate_key<C: Curve>(
+    output: &VRFOutput<C>,
+    members: &[(Point<C>, VRFOutput<C>)],
+    x: &Point<C>,
+    params: &Generators<C>
+) -> bool {
+    aggregate_keys(members, params).is_ok_and(|vk| output.verify(&params.g_tilde, &params.h_tilde, &vk, x))
+}
+
+// Built on the panicking conveniences, like `eval`.
+#[cfg(all(test, not(feature = "no_panic")))]
+mod tests {
+    // use std::time::{SystemTime, Duration};
+
+    use curv::{arithmetic::{BitManipulation, Converter}, elliptic::curves::{Scalar, Ed25519, Point, Curve, Secp256k1, Secp256r1}, BigInt};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use sha2::Digest;
+
+    use crate::error::{EpochError, VrfError};
+    use crate::revocation::{BloomFilter, VerifyOutcome};
+
+    use super::{InversionProof, PROOF_VERSION, ProofMetadata, VRFOutput, VrfInput, BatchVerify, DEFAULT_MAX_BATCH_SIZE, Generators, KeyPair, InputTable, Dst, serialize_columnar, deserialize_columnar, verify_epoch, select_min, find_duplicate_gammas, expand_message_xmd, reduce_wide, wide_hash_len, hash_to_scalar, hash_to_curve, aggregate_keys, verify_aggregate_key, prove_possession, rerandomize, combine_outputs, invert_ct};
+
+    fn test_generic_inversion_proof<C: Curve>() {
         let g_tilde = Point::<C>::generator() * &Scalar::<C>::random();
         let h_tilde = Point::<C>::generator() * &Scalar::<C>::random();
         let gamma = Scalar::<C>::random();
-        let delta = Point::<C>::generator() * &gamma;
-        let theta = Point::<C>::base_point2() * &gamma.invert().unwrap();
+        let delta = Point::<C>::generator() * &gamma;
+        let theta = Point::<C>::base_point2() * &gamma.invert().unwrap();
+
+        let proof = InversionProof::prove(&Point::<C>::generator(), Point::<C>::base_point2(), &g_tilde, &h_tilde, &gamma, &delta, &theta);
+        assert!(proof.verify(&Point::<C>::generator(), Point::<C>::base_point2(), &g_tilde, &h_tilde, &delta, &theta))
+    }
+
+    // The verifier's `t0_point` must be exactly the prover's commitment, not
+    // merely something that happens to hash to the same challenge.
+    fn test_generic_t0_identity<C: Curve>() {
+        let g = Point::<C>::generator().to_point();
+        let h = Point::<C>::base_point2();
+        let params = Generators::<C>::from_seed(b"t0");
+        let gamma = Scalar::<C>::random();
+        let (delta, theta) = (&g * &gamma, h * &gamma.invert().unwrap());
+        let nonces = [(); 4].map(|_| Scalar::<C>::random());
+        let [alpha, beta, tau0, _] = nonces.clone();
+        let proof = InversionProof::prove_with_nonces(nonces, &g, h, &params.g_tilde, &params.h_tilde, &gamma, &delta, &theta, &[]).unwrap();
+        let t0_point = InversionProof::commit_t0(&params.g_tilde, &params.h_tilde, &(&alpha * &beta), &tau0);
+        assert_eq!(t0_point, proof.reconstruct_t0(&params.g_tilde, &params.h_tilde));
+
+        let tampered = InversionProof { zr: &proof.zr + Scalar::from(1), ..proof };
+        assert_ne!(t0_point, tampered.reconstruct_t0(&params.g_tilde, &params.h_tilde));
+    }
+
+    #[test]
+    fn test_ed25519_t0_identity() {
+        test_generic_t0_identity::<Ed25519>()
+    }
+
+    #[test]
+    fn test_secp256k1_t0_identity() {
+        test_generic_t0_identity::<Secp256k1>()
+    }
+
+    fn test_generic_inversion_proof_degenerate<C: Curve>() {
+        let g = Point::<C>::generator().to_point();
+        let h = &g * &Scalar::<C>::random();
+        let params = Generators::<C>::from_seed(b"degenerate");
+        let gamma = Scalar::<C>::random();
+        let (delta, theta) = (&g * &gamma, &h * &gamma.invert().unwrap());
+        let proof = InversionProof::prove(&g, &h, &params.g_tilde, &params.h_tilde, &gamma, &delta, &theta);
+        assert!(proof.verify(&g, &h, &params.g_tilde, &params.h_tilde, &delta, &theta));
+
+        let zero_challenge = InversionProof { x: Scalar::<C>::zero(), ..proof.clone() };
+        assert!(!zero_challenge.verify(&g, &h, &params.g_tilde, &params.h_tilde, &delta, &theta));
+        let identity_t1 = InversionProof { t1_point: Point::<C>::zero(), ..proof };
+        assert!(!identity_t1.verify(&g, &h, &params.g_tilde, &params.h_tilde, &delta, &theta));
+    }
+
+    #[test]
+    fn test_ed25519_inversion_proof_degenerate() {
+        test_generic_inversion_proof_degenerate::<Ed25519>()
+    }
+
+    fn test_generic_inversion_proof_custom_h<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"custom h");
+        let h = hash_to_curve::<C>(&Dst::new(b"protocol context").unwrap(), b"session 1");
+        let gamma = Scalar::<C>::random();
+        let delta = Point::<C>::generator() * &gamma;
+        let theta = &h * &gamma.invert().unwrap();
+
+        let proof = InversionProof::prove(&Point::<C>::generator(), &h, &params.g_tilde, &params.h_tilde, &gamma, &delta, &theta);
+        assert!(proof.verify(&Point::<C>::generator(), &h, &params.g_tilde, &params.h_tilde, &delta, &theta));
+        assert!(!proof.verify(&Point::<C>::generator(), Point::<C>::base_point2(), &params.g_tilde, &params.h_tilde, &delta, &theta));
+    }
+
+    fn test_generic_inversion_proof_tampered<C: Curve>() {
+        let g_tilde = Point::<C>::generator() * &Scalar::<C>::random();
+        let h_tilde = Point::<C>::generator() * &Scalar::<C>::random();
+        let gamma = Scalar::<C>::random();
+        let delta = Point::<C>::generator() * &gamma;
+        let theta = Point::<C>::base_point2() * &gamma.invert().unwrap();
+        let proof = InversionProof::prove(&Point::<C>::generator(), Point::<C>::base_point2(), &g_tilde, &h_tilde, &gamma, &delta, &theta);
+        let verify = |proof: &InversionProof<C>| proof.verify(&Point::<C>::generator(), Point::<C>::base_point2(), &g_tilde, &h_tilde, &delta, &theta);
+        let nonzero = || loop {
+            let s = Scalar::<C>::random();
+            if !s.is_zero() {
+                return s;
+            }
+        };
+        assert!(verify(&proof));
+
+        let mut tampered = proof.clone();
+        tampered.zt = &tampered.zt + nonzero();
+        assert!(!verify(&tampered));
+
+        let mut tampered = proof.clone();
+        tampered.zl = &tampered.zl + nonzero();
+        assert!(!verify(&tampered));
+
+        let mut tampered = proof.clone();
+        tampered.zr = &tampered.zr + nonzero();
+        assert!(!verify(&tampered));
+
+        let mut tampered = proof.clone();
+        tampered.x = &tampered.x + nonzero();
+        assert!(!verify(&tampered));
+
+        let mut tampered = proof;
+        tampered.t1_point = &tampered.t1_point + Point::<C>::generator() * nonzero();
+        assert!(!verify(&tampered));
+    }
+
+    fn test_generic_vrf<C: Curve>() {
+        let sk = Scalar::<C>::random();
+        let vk = Point::<C>::generator() * &sk;
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+
+        let g_tilde = Point::<C>::generator() * &Scalar::<C>::random();
+        let h_tilde = Point::<C>::generator() * &Scalar::<C>::random();
+
+        let output = VRFOutput::eval(&g_tilde, &h_tilde, &vk, &sk, &x);
+        assert!(output.verify(&g_tilde, &h_tilde, &vk, &x));
+    }
+
+    fn test_generic_generators_from_seed<C: Curve>() {
+        let gens = Generators::<C>::from_seed(b"seed");
+        let again = Generators::<C>::from_seed(b"seed");
+        assert_eq!(gens.g_tilde, again.g_tilde);
+        assert_eq!(gens.h_tilde, again.h_tilde);
+        assert_ne!(gens.g_tilde, gens.h_tilde);
+        assert_ne!(gens.g_tilde, Generators::<C>::from_seed(b"other seed").g_tilde);
+
+        let sk = Scalar::<C>::random();
+        let vk = Point::<C>::generator() * &sk;
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let output = VRFOutput::eval(&gens.g_tilde, &gens.h_tilde, &vk, &sk, &x);
+        assert!(output.verify(&gens.g_tilde, &gens.h_tilde, &vk, &x));
+    }
+
+    fn test_generic_clone_eq_debug<C: Curve>() {
+        let sk = Scalar::<C>::random();
+        let vk = Point::<C>::generator() * &sk;
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let gens = Generators::<C>::from_seed(b"clone");
+
+        let output = VRFOutput::eval(&gens.g_tilde, &gens.h_tilde, &vk, &sk, &x);
+        let rebuilt = VRFOutput {
+            y: output.y.clone(),
+            u: output.u.clone(),
+            r: output.r.clone(),
+            legacy: false
+        };
+        assert_eq!(output, rebuilt);
+        assert_eq!(output.r, output.clone().r);
+        assert_ne!(output, VRFOutput::eval(&gens.g_tilde, &gens.h_tilde, &vk, &sk, &x));
+        assert!(format!("{:?}", output).contains(&hex::encode(&*output.u.to_bytes(true))));
+    }
+
+    fn test_generic_verify_aggregate_key<C: Curve>() {
+        let keypairs: Vec<_> = (0..3).map(|_| KeyPair::<C>::generate()).collect();
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let params = Generators::<C>::from_seed(b"aggregate");
+        let members: Vec<_> = keypairs.iter()
+            .map(|keypair| (keypair.public_key().clone(), prove_possession(keypair, &params).unwrap()))
+            .collect();
+
+        let sk: Scalar<C> = keypairs.iter().map(|keypair| keypair.secret_key()).sum();
+        let vk = Point::<C>::generator() * &sk;
+        assert_eq!(Ok(vk.clone()), aggregate_keys(&members, &params));
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, &vk, &sk, &x);
+        assert!(verify_aggregate_key(&output, &members, &x, &params));
+
+        let single = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypairs[0].public_key(), keypairs[0].secret_key(), &x);
+        assert!(!verify_aggregate_key(&single, &members, &x, &params));
+
+        // A rogue key cancelling the others', with no secret behind it, and
+        // an honest member's proof replayed for it.
+        let target = KeyPair::<C>::generate();
+        let rogue = target.public_key() - keypairs[0].public_key();
+        let replayed = vec![members[0].clone(), (rogue.clone(), members[0].1.clone())];
+        assert_eq!(Err(VrfError::ProofRejected), aggregate_keys(&replayed, &params));
+        let forged = VRFOutput::eval(&params.g_tilde, &params.h_tilde, target.public_key(), target.secret_key(), &x);
+        assert!(!verify_aggregate_key(&forged, &replayed, &x, &params));
+        let swapped = vec![(members[0].0.clone(), members[1].1.clone())];
+        assert_eq!(Err(VrfError::ProofRejected), aggregate_keys(&swapped, &params));
+        assert_eq!(Err(VrfError::IdentityPoint), aggregate_keys::<C>(&[], &params));
+    }
+
+    fn test_generic_keypair<C: Curve>() {
+        let keypair = KeyPair::<C>::generate();
+        assert_eq!(*keypair.public_key(), Point::<C>::generator() * keypair.secret_key());
+        assert!(matches!(KeyPair::<C>::from_secret(Scalar::<C>::zero()), Err(VrfError::ZeroSecretKey)));
+
+        let params = Generators::<C>::from_seed(b"rerandomize");
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        for _ in 0..3 {
+            let blind = Scalar::<C>::random();
+            let (sk, vk) = rerandomize(keypair.secret_key(), &blind);
+            assert_eq!(vk, keypair.public_key() + Point::<C>::generator() * &blind);
+            let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, &vk, &sk, &x);
+            assert!(output.verify(&params.g_tilde, &params.h_tilde, &vk, &x));
+            assert!(!output.verify(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x));
+        }
+    }
+
+    fn test_generic_verify_with_metadata<C: Curve>() -> ProofMetadata {
+        let keypair = KeyPair::<C>::generate();
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let params = Generators::<C>::from_seed(b"metadata");
+        let (vk, sk) = (keypair.public_key(), keypair.secret_key());
+
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, vk, sk, &x);
+        let metadata = output.verify_with_metadata(&params.g_tilde, &params.h_tilde, vk, &x).unwrap();
+        assert_eq!(C::CURVE_NAME, metadata.curve);
+        assert_eq!("rka-vrf", metadata.suite);
+        assert_eq!(64, metadata.output_len);
+        assert_eq!(output.to_proof_bytes().len(), metadata.proof_len);
+        assert!(matches!(
+            output.verify_with_metadata(&params.g_tilde, &params.h_tilde, vk, &Point::<C>::generator().to_point()),
+            Err(VrfError::ProofRejected)
+        ));
+
+        assert_eq!(Ok(()), output.verify_detailed(&params.g_tilde, &params.h_tilde, vk, &x));
+        let mut tampered = output.clone();
+        tampered.y = &tampered.y + BigInt::from(1);
+        tampered.r.zt = &tampered.r.zt + Scalar::<C>::from(1);
+        assert_eq!(
+            Err(vec![VrfError::OutputMismatch, VrfError::ProofRejected]),
+            tampered.verify_detailed(&params.g_tilde, &params.h_tilde, vk, &x)
+        );
+        assert!(matches!(
+            output.verify_detailed(&params.g_tilde, &params.h_tilde, &Point::<C>::zero(), &x),
+            Err(errors) if errors[0] == VrfError::IdentityPoint
+        ));
+        metadata
+    }
+
+    #[test]
+    fn test_ed25519_verify_with_metadata() {
+        let metadata = test_generic_verify_with_metadata::<Ed25519>();
+        assert_eq!("ed25519", metadata.curve);
+        // version and curve id, u and t1_point as 32-byte points, zt, zl, zr
+        // and x as 32-byte scalars
+        assert_eq!(194, metadata.proof_len);
+    }
+
+    #[test]
+    fn test_secp256k1_verify_with_metadata() {
+        // as on ed25519, but with 33-byte compressed points
+        assert_eq!(196, test_generic_verify_with_metadata::<Secp256k1>().proof_len);
+    }
+
+    #[test]
+    fn test_secp256r1_verify_with_metadata() {
+        assert_eq!(196, test_generic_verify_with_metadata::<Secp256r1>().proof_len);
+    }
+
+    fn test_generic_input_table<C: Curve>() {
+        let keypair = KeyPair::<C>::generate();
+        let params = Generators::<C>::from_seed(b"table");
+        let inputs: [&[u8]; 3] = [b"alice", b"bob", b"carol"];
+        let table = InputTable::<C>::new(&inputs);
+        assert_eq!(3, table.len());
+
+        for (index, msg) in inputs.iter().enumerate() {
+            let indexed = table.eval_indexed(&keypair, &params, index).unwrap();
+            let direct = VRFOutput::eval_bytes(&keypair, &params, msg);
+            assert_eq!(direct.y, indexed.y);
+            assert_eq!(direct.u, indexed.u);
+            assert!(indexed.verify_bytes(&params, keypair.public_key(), msg));
+            assert!(indexed.verify(&params.g_tilde, &params.h_tilde, keypair.public_key(), table.point(index).unwrap()));
+        }
+        assert!(table.eval_indexed(&keypair, &params, 3).is_none());
+
+        let vk = keypair.public_key();
+        let input = VrfInput::<C>::from_message(b"application message");
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, vk, keypair.secret_key(), input.as_point());
+        assert!(output.verify(&params.g_tilde, &params.h_tilde, vk, input.as_point()));
+        assert!(output.verify_bytes(&params, vk, b"application message"));
+        assert!(!output.verify(&params.g_tilde, &params.h_tilde, vk, VrfInput::from_message(b"other message").as_point()));
+
+        let x: Point<C> = input.into();
+        assert!(output.verify(&params.g_tilde, &params.h_tilde, vk, &x));
+
+        let empty = VRFOutput::eval_bytes(&keypair, &params, b"");
+        assert!(empty.verify_bytes(&params, vk, b""));
+        assert!(!empty.verify_bytes(&params, vk, &[0]));
+    }
+
+    fn test_generic_prove_with_rng<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"rng");
+        let gamma = Scalar::<C>::random();
+        let delta = Point::<C>::generator() * &gamma;
+        let theta = Point::<C>::base_point2() * &gamma.invert().unwrap();
+        let prove = |seed: u64| InversionProof::prove_with_rng(
+            &mut StdRng::seed_from_u64(seed),
+            &Point::<C>::generator(),
+            Point::<C>::base_point2(),
+            &params.g_tilde,
+            &params.h_tilde,
+            &gamma,
+            &delta,
+            &theta
+        );
+
+        let proof = prove(7);
+        assert_eq!(proof, prove(7));
+        assert_eq!(format!("{:?}", proof), format!("{:?}", prove(7)));
+        assert_ne!(proof, prove(8));
+        assert!(proof.verify(&Point::<C>::generator(), Point::<C>::base_point2(), &params.g_tilde, &params.h_tilde, &delta, &theta));
+    }
+
+    #[test]
+    fn test_ed25519_prove_with_rng() {
+        test_generic_prove_with_rng::<Ed25519>()
+    }
+
+    fn test_generic_columnar<C: Curve>() {
+        let keypair = KeyPair::<C>::generate();
+        let params = Generators::<C>::from_seed(b"columnar");
+        let outputs: Vec<_> = (0..100u32)
+            .map(|i| VRFOutput::eval_bytes(&keypair, &params, &i.to_be_bytes()))
+            .collect();
+
+        let blob = serialize_columnar(&outputs);
+        let decoded = deserialize_columnar::<C>(&blob).unwrap();
+        assert_eq!(outputs, decoded);
+        assert!(decoded[42].verify_bytes(&params, keypair.public_key(), &42u32.to_be_bytes()));
+
+        let mut truncated = blob.clone();
+        truncated.zr.truncate(truncated.zr.len() - 1);
+        assert_eq!(Err(VrfError::InvalidLength), deserialize_columnar::<C>(&truncated));
+
+        let mut dropped = blob;
+        let point_len = dropped.u.len() / 100;
+        dropped.u.truncate(99 * point_len);
+        assert_eq!(Err(VrfError::InvalidLength), deserialize_columnar::<C>(&dropped));
+    }
+
+    #[test]
+    fn test_ed25519_columnar() {
+        test_generic_columnar::<Ed25519>()
+    }
+
+    #[cfg(feature = "metrics")]
+    fn test_generic_verify_with_metrics<C: Curve>() {
+        let keypair = KeyPair::<C>::generate();
+        let params = Generators::<C>::from_seed(b"metrics");
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+
+        // order checks on vk, x and t1_point; hash_output; hash_point
+        // (hash-to-curve, no mul); seven muls, four adds and the challenge
+        // hash in the inversion proof
+        let (valid, ops) = output.verify_with_metrics(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x);
+        assert!(valid);
+        assert_eq!(10, ops.point_muls);
+        assert_eq!(4, ops.point_adds);
+        assert_eq!(3, ops.hashes);
+
+        // no shared work across a batch: each proof costs the same as alone
+        let mut batch_ops = crate::metrics::OpCounts::default();
+        let batch = vec![(&output, keypair.public_key(), &x); 3];
+        assert_eq!(Ok(true), BatchVerify::new(&params).verify_counted(&batch, &mut batch_ops));
+        assert_eq!(crate::metrics::OpCounts { point_muls: 30, point_adds: 12, hashes: 9 }, batch_ops);
+
+        // a wrong input fails the output hash after only the order checks
+        let other = Point::<C>::generator() * &Scalar::<C>::random();
+        let (valid, ops) = output.verify_with_metrics(&params.g_tilde, &params.h_tilde, keypair.public_key(), &other);
+        assert!(!valid);
+        assert_eq!(2, ops.point_muls);
+        assert_eq!(0, ops.point_adds);
+        assert_eq!(1, ops.hashes);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_ed25519_verify_with_metrics() {
+        test_generic_verify_with_metrics::<Ed25519>()
+    }
+
+    fn test_generic_verify_lazy<C: Curve>() {
+        let keypair = KeyPair::<C>::generate();
+        let params = Generators::<C>::from_seed(b"lazy");
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+
+        let (y, handle) = output.verify_lazy(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x);
+        assert_eq!(output.y, y);
+        assert!(handle.finalize());
+
+        let mut tampered = output.clone();
+        tampered.r.zt = &tampered.r.zt + Scalar::<C>::from(1);
+        let (y, handle) = tampered.verify_lazy(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x);
+        assert_eq!(output.y, y);
+        assert!(!handle.finalize());
+
+        let other = Point::<C>::generator() * &Scalar::<C>::random();
+        let (_, handle) = output.verify_lazy(&params.g_tilde, &params.h_tilde, keypair.public_key(), &other);
+        assert!(!handle.finalize());
+    }
+
+    #[test]
+    fn test_ed25519_verify_lazy() {
+        test_generic_verify_lazy::<Ed25519>()
+    }
+
+    fn test_generic_verify_epoch<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"epoch");
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let keypairs: Vec<_> = (0..4).map(|_| KeyPair::<C>::generate()).collect();
+        let registered = keypairs.iter().map(|k| k.public_key().to_bytes(true).to_vec()).collect();
+        let proofs: Vec<_> = keypairs.iter().map(|k| (
+            VRFOutput::eval(&params.g_tilde, &params.h_tilde, k.public_key(), k.secret_key(), &x),
+            k.public_key().clone()
+        )).collect();
+        assert_eq!(Ok(()), verify_epoch(&proofs, &registered, &x, &params));
+
+        let mut duplicate = proofs.clone();
+        duplicate[3] = duplicate[1].clone();
+        assert_eq!(Err(EpochError::DuplicateKey(3)), verify_epoch(&duplicate, &registered, &x, &params));
+
+        assert_eq!(Err(EpochError::MissingKeys(1)), verify_epoch(&proofs[..3], &registered, &x, &params));
+
+        let outsider = KeyPair::<C>::generate();
+        let mut unregistered = proofs.clone();
+        unregistered.push((
+            VRFOutput::eval(&params.g_tilde, &params.h_tilde, outsider.public_key(), outsider.secret_key(), &x),
+            outsider.public_key().clone()
+        ));
+        assert_eq!(Err(EpochError::UnregisteredKey(4)), verify_epoch(&unregistered, &registered, &x, &params));
+
+        let mut invalid = proofs;
+        invalid[2].0 = invalid[0].0.clone();
+        assert_eq!(Err(EpochError::InvalidProof(2)), verify_epoch(&invalid, &registered, &x, &params));
+    }
+
+    #[test]
+    fn test_ed25519_verify_epoch() {
+        test_generic_verify_epoch::<Ed25519>()
+    }
+
+    fn test_generic_normalize_output<C: Curve>() {
+        let keypair = KeyPair::<C>::generate();
+        let params = Generators::<C>::from_seed(b"normalize");
+        let output = VRFOutput::eval_bytes(&keypair, &params, b"message");
+        let normalized: [u8; 64] = output.normalize_output();
+        assert_eq!(normalized, VRFOutput::eval_bytes(&keypair, &params, b"message").normalize_output());
+        assert_ne!(normalized, VRFOutput::eval_bytes(&keypair, &params, b"other").normalize_output());
+    }
+
+    #[test]
+    fn test_ed25519_normalize_output() {
+        test_generic_normalize_output::<Ed25519>()
+    }
+
+    fn test_generic_select_min<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"select");
+        let outputs: Vec<_> = (0..8)
+            .map(|_| VRFOutput::eval_bytes(&KeyPair::<C>::generate(), &params, b"round 1"))
+            .collect();
+        let min = select_min(&outputs).unwrap();
+        assert!(outputs.iter().all(|o| min.output_value() <= o.output_value()));
+        assert_eq!(outputs.iter().map(|o| o.output_value()).min(), Some(min.output_value()));
+        assert!(select_min::<C>(&[]).is_none());
+    }
+
+    #[test]
+    fn test_ed25519_select_min() {
+        test_generic_select_min::<Ed25519>()
+    }
+
+    fn test_generic_combine_outputs<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"combine");
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let outputs: Vec<_> = (0..4).map(|_| {
+            let keypair = KeyPair::<C>::generate();
+            VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x)
+        }).collect();
+        let refs: Vec<_> = outputs.iter().collect();
+        let beacon = combine_outputs(&refs);
+        assert_eq!(beacon, combine_outputs(&refs));
+        let reordered = [refs[2], refs[0], refs[3], refs[1]];
+        assert_eq!(beacon, combine_outputs(&reordered));
+        assert_ne!(beacon, combine_outputs(&refs[..3]));
+    }
+
+    #[test]
+    fn test_ed25519_combine_outputs() {
+        test_generic_combine_outputs::<Ed25519>()
+    }
+
+    fn test_generic_find_duplicate_gammas<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"duplicates");
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let keypairs: Vec<_> = (0..3).map(|_| KeyPair::<C>::generate()).collect();
+        let eval = |keypair: &KeyPair<C>| VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+        let mut outputs: Vec<_> = keypairs.iter().map(eval).collect();
+        assert_eq!(None, find_duplicate_gammas(&outputs));
+        assert_eq!(None, find_duplicate_gammas::<C>(&[]));
+
+        // A second, differently randomized proof from the same key.
+        outputs.push(eval(&keypairs[1]));
+        assert_ne!(outputs[1], outputs[3]);
+        assert_eq!(Some((1, 3)), find_duplicate_gammas(&outputs));
+    }
+
+    #[test]
+    fn test_ed25519_find_duplicate_gammas() {
+        test_generic_find_duplicate_gammas::<Ed25519>()
+    }
+
+    fn test_generic_verify_proof_only<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"proof only");
+        let keypair = KeyPair::<C>::generate();
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let mut output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+        output.y = BigInt::from(42);
+        assert!(!output.verify(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x));
+        assert!(output.verify_proof_only(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x));
+        let other_x = Point::<C>::generator() * &Scalar::<C>::random();
+        assert!(!output.verify_proof_only(&params.g_tilde, &params.h_tilde, keypair.public_key(), &other_x));
+        output.r.zl = &output.r.zl + Scalar::<C>::from(1);
+        assert!(!output.verify_proof_only(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x));
+    }
+
+    #[test]
+    fn test_ed25519_verify_proof_only() {
+        test_generic_verify_proof_only::<Ed25519>()
+    }
+
+    fn test_generic_hash_point_not_generator_multiple<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"hash point");
+        let keypair = KeyPair::<C>::generate();
+        let x = hash_to_curve::<C>(&Dst::new(b"test").unwrap(), b"fixed input");
+        let base = VRFOutput::hash_point(keypair.public_key(), &x);
+        // The old construction, `G * H(vk, x)`, with its known discrete log.
+        let digest = sha2::Sha512::new().chain(&*keypair.public_key().to_bytes(true)).chain(&*x.to_bytes(true));
+        let known = Point::<C>::generator() * Scalar::<C>::from_bigint(&reduce_wide(digest, Scalar::<C>::group_order()));
+        assert_ne!(known, base);
+        assert_ne!(Point::<C>::generator().to_point(), base);
+        assert_eq!(base, VRFOutput::hash_point(keypair.public_key(), &x));
+
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+        assert_eq!(base, &output.u * keypair.secret_key());
+        assert!(output.verify(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x));
+    }
+
+    #[test]
+    fn test_ed25519_hash_point_not_generator_multiple() {
+        test_generic_hash_point_not_generator_multiple::<Ed25519>()
+    }
+
+    #[test]
+    fn test_secp256k1_hash_point_not_generator_multiple() {
+        test_generic_hash_point_not_generator_multiple::<Secp256k1>()
+    }
+
+    fn test_generic_eval_encrypted<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"encrypted");
+        let signer = KeyPair::<C>::generate();
+        let recipient = KeyPair::<C>::generate();
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let (vk, sk) = (signer.public_key(), signer.secret_key());
+
+        let encrypted = VRFOutput::eval_encrypted(recipient.public_key(), vk, sk, &x);
+        assert!(encrypted.verify(recipient.public_key(), vk, &x));
+        let plain = VRFOutput::eval(&params.g_tilde, &params.h_tilde, vk, sk, &x);
+        assert_eq!(plain.y, encrypted.decrypt(recipient.secret_key(), &x));
+
+        let other = KeyPair::<C>::generate();
+        assert_ne!(plain.y, encrypted.decrypt(other.secret_key(), &x));
+        assert!(!encrypted.verify(other.public_key(), vk, &x));
+        assert!(!encrypted.verify(recipient.public_key(), other.public_key(), &x));
+
+        let mut tampered = encrypted;
+        tampered.c2 = &tampered.c2 + Point::<C>::generator();
+        assert!(!tampered.verify(recipient.public_key(), vk, &x));
+
+        let identity = Point::<C>::zero();
+        assert_eq!(Some(VrfError::IdentityPoint), VRFOutput::try_eval_encrypted(&identity, vk, sk, &x).err());
+        assert_eq!(Some(VrfError::IdentityPoint), VRFOutput::try_eval_encrypted(recipient.public_key(), &identity, sk, &x).err());
+        assert_eq!(Some(VrfError::IdentityPoint), VRFOutput::try_eval_encrypted(recipient.public_key(), vk, sk, &identity).err());
+    }
+
+    #[test]
+    fn test_ed25519_eval_encrypted() {
+        test_generic_eval_encrypted::<Ed25519>()
+    }
+
+    fn test_generic_verify_not_revoked<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"revocation");
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let revoked_key = KeyPair::<C>::generate();
+        let active_key = KeyPair::<C>::generate();
+        let mut revoked = BloomFilter::new(1024, 4);
+        revoked.insert(&revoked_key.public_key().to_bytes(true));
+
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, revoked_key.public_key(), revoked_key.secret_key(), &x);
+        assert_eq!(VerifyOutcome::Revoked, output.verify_not_revoked(revoked_key.public_key(), &x, &params, &revoked));
+
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, active_key.public_key(), active_key.secret_key(), &x);
+        assert_eq!(VerifyOutcome::Valid, output.verify_not_revoked(active_key.public_key(), &x, &params, &revoked));
+        let other_x = Point::<C>::generator() * &Scalar::<C>::random();
+        assert_eq!(VerifyOutcome::Invalid, output.verify_not_revoked(active_key.public_key(), &other_x, &params, &revoked));
+    }
+
+    #[test]
+    fn test_ed25519_verify_not_revoked() {
+        test_generic_verify_not_revoked::<Ed25519>()
+    }
+
+    fn test_generic_identity_inputs<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"identity");
+        let keypair = KeyPair::<C>::generate();
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let zero = Point::<C>::zero();
+        assert_eq!(
+            Err(VrfError::IdentityPoint),
+            VRFOutput::try_eval(&params.g_tilde, &params.h_tilde, &zero, keypair.secret_key(), &x)
+        );
+        assert_eq!(
+            Err(VrfError::IdentityPoint),
+            VRFOutput::try_eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &zero)
+        );
+
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+        assert!(!output.verify(&params.g_tilde, &params.h_tilde, &zero, &x));
+        assert!(!output.verify(&params.g_tilde, &params.h_tilde, keypair.public_key(), &zero));
+        assert_eq!(
+            Err(VrfError::IdentityPoint),
+            output.verify_with_metadata(&params.g_tilde, &params.h_tilde, &zero, &x)
+        );
+        assert!(!output.verify_lazy(&params.g_tilde, &params.h_tilde, &zero, &x).1.finalize());
+        assert!(!output.verify_lazy(&params.g_tilde, &params.h_tilde, keypair.public_key(), &zero).1.finalize());
+
+        let recipient = KeyPair::<C>::generate();
+        let encrypted = VRFOutput::eval_encrypted(recipient.public_key(), keypair.public_key(), keypair.secret_key(), &x);
+        assert!(encrypted.verify(recipient.public_key(), keypair.public_key(), &x));
+        assert!(!encrypted.verify(recipient.public_key(), &zero, &x));
+        assert!(!encrypted.verify(recipient.public_key(), keypair.public_key(), &zero));
+    }
+
+    #[test]
+    fn test_ed25519_identity_inputs() {
+        test_generic_identity_inputs::<Ed25519>()
+    }
+
+    fn test_generic_generator_is_static<C: Curve>() {
+        let fresh = Point::<C>::generator();
+        assert!(std::ptr::eq(fresh.as_point(), Point::<C>::generator().as_point()));
+        assert_eq!(fresh.to_point(), *Point::<C>::generator().as_point());
+        let s = Scalar::<C>::random();
+        assert_eq!(fresh * &s, fresh.to_point() * &s);
+    }
+
+    #[test]
+    fn test_ed25519_generator_is_static() {
+        test_generic_generator_is_static::<Ed25519>()
+    }
+
+    fn test_generic_verify_canonical<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"canonical");
+        let keypair = KeyPair::<C>::generate();
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+        let bytes = output.to_proof_bytes();
+        assert_eq!(Ok(()), VRFOutput::verify_canonical(&bytes, keypair.public_key(), &x, &params));
+
+        let other_x = Point::<C>::generator() * &Scalar::<C>::random();
+        assert_eq!(
+            Err(VrfError::ProofRejected),
+            VRFOutput::verify_canonical(&bytes, keypair.public_key(), &other_x, &params)
+        );
+        // One byte short of the current encoding is the length of a version 1
+        // encoding, so trim two.
+        assert_eq!(
+            Err(VrfError::InvalidLength),
+            VRFOutput::verify_canonical(&bytes[2..], keypair.public_key(), &x, &params)
+        );
+        assert_eq!(
+            Err(VrfError::InvalidLength),
+            VRFOutput::verify_canonical(&bytes[..bytes.len() - 1], keypair.public_key(), &x, &params)
+        );
+    }
+
+    #[test]
+    fn test_ed25519_verify_canonical() {
+        test_generic_verify_canonical::<Ed25519>()
+    }
+
+    #[test]
+    fn test_ed25519_verify_canonical_unreduced_scalar() {
+        let params = Generators::<Ed25519>::from_seed(b"canonical");
+        let keypair = KeyPair::<Ed25519>::generate();
+        let x = Point::<Ed25519>::generator() * &Scalar::<Ed25519>::random();
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+        let mut bytes = output.to_proof_bytes();
+
+        // zt + l decodes to a scalar that multiplies like zt, but is not the
+        // canonical encoding.
+        let zt_offset = 66;
+        let mut zt = bytes[zt_offset..zt_offset + 32].to_vec();
+        zt.reverse();
+        let mut unreduced = (BigInt::from_bytes(&zt) + Scalar::<Ed25519>::group_order()).to_bytes();
+        unreduced.reverse();
+        assert!(Scalar::<Ed25519>::from_bytes(&unreduced).is_ok());
+        bytes[zt_offset..zt_offset + 32].copy_from_slice(&unreduced);
+        assert_eq!(
+            Err(VrfError::NonCanonicalScalar),
+            VRFOutput::verify_canonical(&bytes, keypair.public_key(), &x, &params)
+        );
+    }
+
+    fn test_generic_prehashed<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"prehashed");
+        let keypair = KeyPair::<C>::generate();
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let base = VRFOutput::hash_point(keypair.public_key(), &x);
+        let output = VRFOutput::eval_prehashed(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x, &base);
+        let plain = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+        assert_eq!(plain.y, output.y);
+        assert_eq!(plain.u, output.u);
+        assert!(output.verify(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x));
+        assert!(plain.verify_prehashed(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x, &base));
+
+        let wrong_base = Point::<C>::generator() * &Scalar::<C>::random();
+        assert!(!plain.verify_prehashed(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x, &wrong_base));
+
+        // The proof equations only involve `vk`, `u` and the base point, so with
+        // the base point passed in they hold for any `x`; the challenge still
+        // names the original `(vk, x, u)`, and a bare `InversionProof::verify`
+        // without it fails too.
+        assert!(!plain.r.verify(&Point::<C>::generator().to_point(), &base, &params.g_tilde, &params.h_tilde, keypair.public_key(), &plain.u));
+        let other_x = Point::<C>::generator() * &Scalar::<C>::random();
+        let mut moved = plain.clone();
+        moved.y = VRFOutput::hash_output(&other_x, &plain.u);
+        assert!(!moved.verify_prehashed(&params.g_tilde, &params.h_tilde, keypair.public_key(), &other_x, &base));
+        assert!(!moved.verify(&params.g_tilde, &params.h_tilde, keypair.public_key(), &other_x));
+
+        let other = KeyPair::<C>::generate();
+        assert!(!plain.verify_prehashed(&params.g_tilde, &params.h_tilde, other.public_key(), &x, &base));
+    }
+
+    #[test]
+    fn test_ed25519_prehashed() {
+        test_generic_prehashed::<Ed25519>()
+    }
+
+    fn test_generic_context<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"context");
+        let keypair = KeyPair::<C>::generate();
+        let (vk, sk) = (keypair.public_key(), keypair.secret_key());
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let epoch1 = VRFOutput::eval_with_context(&params.g_tilde, &params.h_tilde, vk, sk, &x, b"epoch-1").unwrap();
+        let epoch2 = VRFOutput::eval_with_context(&params.g_tilde, &params.h_tilde, vk, sk, &x, b"epoch-2").unwrap();
+        assert_ne!(epoch1.output_value(), epoch2.output_value());
+        assert!(epoch1.verify_with_context(&params.g_tilde, &params.h_tilde, vk, &x, b"epoch-1"));
+        assert!(!epoch1.verify_with_context(&params.g_tilde, &params.h_tilde, vk, &x, b"epoch-2"));
+        assert!(epoch2.verify_with_context(&params.g_tilde, &params.h_tilde, vk, &x, b"epoch-2"));
+        assert!(!epoch2.verify(&params.g_tilde, &params.h_tilde, vk, &x));
+
+        let plain = VRFOutput::eval(&params.g_tilde, &params.h_tilde, vk, sk, &x);
+        assert!(plain.verify_with_context(&params.g_tilde, &params.h_tilde, vk, &x, b""));
+
+        let height = 1024u64.to_le_bytes();
+        let bound = VRFOutput::eval_with_associated_data(&params.g_tilde, &params.h_tilde, vk, sk, &x, &height).unwrap();
+        assert!(bound.verify_with_associated_data(&params.g_tilde, &params.h_tilde, vk, &x, &height));
+        assert_eq!(plain.y, bound.y);
+        assert!(!bound.verify_with_associated_data(&params.g_tilde, &params.h_tilde, vk, &x, &1025u64.to_le_bytes()));
+        assert!(!bound.verify_with_associated_data(&params.g_tilde, &params.h_tilde, vk, &x, &[]));
+        assert!(!bound.verify(&params.g_tilde, &params.h_tilde, vk, &x));
+
+        let mut outputs = Vec::new();
+        for len in [32, 64, 128] {
+            let (bytes, output) = VRFOutput::eval_with_output_len(&params.g_tilde, &params.h_tilde, vk, sk, &x, len).unwrap();
+            assert_eq!(Some(&bytes), output.verify_with_output_len(&params.g_tilde, &params.h_tilde, vk, &x, len).as_ref());
+            assert_eq!(None, output.verify_with_output_len(&params.g_tilde, &params.h_tilde, vk, &x, len + 1));
+            assert!(!output.verify(&params.g_tilde, &params.h_tilde, vk, &x));
+            let colliding = [&b"rka-vrf-output-len"[..], &(len as u64).to_le_bytes()].concat();
+            assert!(!output.verify_with_context(&params.g_tilde, &params.h_tilde, vk, &x, &colliding));
+            assert_eq!(len, bytes.len());
+            outputs.push(bytes);
+        }
+        assert_ne!(outputs[0][..], outputs[1][..32]);
+        assert_ne!(outputs[1][..], outputs[2][..64]);
+    }
+
+    #[test]
+    fn test_ed25519_context() {
+        test_generic_context::<Ed25519>()
+    }
+
+    fn test_generic_prove_deterministic<C: Curve>() {
+        let g = Point::<C>::generator().to_point();
+        let params = Generators::<C>::from_seed(b"deterministic");
+        let gamma = Scalar::<C>::random();
+        let h = &g * &Scalar::<C>::random();
+        let other_h = &g * &Scalar::<C>::random();
+        let statement = |h: &Point<C>| (&g * &gamma, h * &gamma.invert().unwrap());
 
-        let proof = InversionProof::prove(&Point::<C>::generator(), Point::<C>::base_point2(), &g_tilde, &h_tilde, &gamma, &delta, &theta);
-        assert_eq!(true, proof.verify(&Point::<C>::generator(), Point::<C>::base_point2(), &g_tilde, &h_tilde, &delta, &theta))
+        let (delta, theta) = statement(&h);
+        let proof = InversionProof::prove_deterministic(&g, &h, &params.g_tilde, &params.h_tilde, &gamma, &delta, &theta);
+        assert!(proof.verify(&g, &h, &params.g_tilde, &params.h_tilde, &delta, &theta));
+        assert_eq!(proof, InversionProof::prove_deterministic(&g, &h, &params.g_tilde, &params.h_tilde, &gamma, &delta, &theta));
+
+        let (other_delta, other_theta) = statement(&other_h);
+        let nonces = InversionProof::deterministic_nonces(&g, &h, &params.g_tilde, &params.h_tilde, &gamma, &delta, &theta, &[]);
+        let other = InversionProof::deterministic_nonces(&g, &other_h, &params.g_tilde, &params.h_tilde, &gamma, &other_delta, &other_theta, &[]);
+        assert!(nonces.iter().zip(&other).all(|(a, b)| a != b));
+        assert!(nonces.iter().enumerate().all(|(i, a)| nonces[i + 1..].iter().all(|b| a != b)));
+
+        let keypair = KeyPair::<C>::generate();
+        let (vk, sk) = (keypair.public_key(), keypair.secret_key());
+        let x = &g * &Scalar::<C>::random();
+        let output = VRFOutput::eval_deterministic(&params.g_tilde, &params.h_tilde, vk, sk, &x).unwrap();
+        assert!(output.verify(&params.g_tilde, &params.h_tilde, vk, &x));
+        assert_eq!(output, VRFOutput::eval_deterministic(&params.g_tilde, &params.h_tilde, vk, sk, &x).unwrap());
+        assert_eq!(Ok(output.clone()), VRFOutput::from_proof_bytes(&output.to_proof_bytes(), &x));
     }
 
-    fn test_generic_vrf<C: Curve>() {
-        let sk = Scalar::<C>::random();
-        let vk = Point::<C>::generator() * &sk;
+    #[test]
+    fn test_ed25519_prove_deterministic() {
+        test_generic_prove_deterministic::<Ed25519>()
+    }
+
+    fn test_generic_inversion_proof_bytes<C: Curve>() {
+        let g = Point::<C>::generator().to_point();
+        let h = &g * &Scalar::<C>::random();
+        let params = Generators::<C>::from_seed(b"bytes");
+        let gamma = Scalar::<C>::random();
+        let (delta, theta) = (&g * &gamma, &h * &gamma.invert().unwrap());
+        let proof = InversionProof::prove(&g, &h, &params.g_tilde, &params.h_tilde, &gamma, &delta, &theta);
+        let bytes = proof.to_bytes();
+        assert_eq!(Ok(proof), InversionProof::<C>::from_bytes(&bytes));
+        assert_eq!(Err(VrfError::InvalidLength), InversionProof::<C>::from_bytes(&bytes[..bytes.len() - 1]));
+        assert!(InversionProof::<C>::from_bytes(&vec![0xff; bytes.len()]).is_err());
+        assert_eq!(InversionProof::<C>::serialized_len(), bytes.len());
+        assert_eq!(Err(VrfError::InvalidLength), InversionProof::<C>::from_bytes(&[&bytes[..], &[0]].concat()));
+    }
+
+    #[test]
+    fn test_ed25519_proof_bytes_version_1() {
+        // `to_proof_bytes` before the version byte, from `eval_deterministic`
+        // on the statement below.
+        let legacy = hex::decode("72fa06a07f44524ec8002752250069fef605406ef6790d9c33762e603c19fac60115d9d22a5ff71358535c90e0eaf5c90b5cfaa3555343975b52d5787d6f044cf840adce392c6d4aa279196655ec53520dfa0fc8f111ce438324fecf122ea0b10bccc0a1c0527c609c3e1a122399aa2361c354b79f6e07cc6db9c2553f640fae0dde916f8a2375a65a9e8a9a28930be8150da1599d2fc3f386efda00e301994a096179ee076d88f0ab867186278d41b23c43b65642641f34e0efaa459a5f2dc10e").unwrap();
+        let params = Generators::<Ed25519>::from_seed(b"legacy");
+        let keypair = KeyPair::<Ed25519>::from_secret(Scalar::from(1234)).unwrap();
+        let x = hash_to_curve::<Ed25519>(&Dst::new(b"test").unwrap(), b"legacy");
+        assert_eq!(Ok(()), VRFOutput::verify_canonical(&legacy, keypair.public_key(), &x, &params));
+
+        // Re-encoding keeps the unbound challenge, so it takes version 2.
+        let output = VRFOutput::from_proof_bytes(&legacy, &x).unwrap();
+        let version_2 = output.to_proof_bytes();
+        assert_eq!(legacy.len() + 1, version_2.len());
+        assert_eq!(0x82, version_2[32]);
+        assert_eq!(Ok(output), VRFOutput::from_proof_bytes(&version_2, &x));
+        assert_eq!(Ok(()), VRFOutput::verify_canonical(&version_2, keypair.public_key(), &x, &params));
+
+        // Marking it current would demand the statement-bound challenge.
+        let mut current = version_2;
+        current[32] = PROOF_VERSION;
+        assert_eq!(Err(VrfError::ProofRejected), VRFOutput::verify_canonical(&current, keypair.public_key(), &x, &params));
+
+        // The bare inversion proof is unchanged, so it decodes at any version.
+        let proof = InversionProof::<Ed25519>::from_bytes(&legacy[32..]).unwrap();
+        assert_eq!(Ok(proof.clone()), InversionProof::from_bytes(&current[32..]));
+        assert_eq!(PROOF_VERSION, proof.to_bytes()[0]);
+    }
+
+    #[test]
+    fn test_ed25519_proof_bytes_version_3() {
+        // `to_proof_bytes` from `eval_deterministic` on the same statement as
+        // the version 1 blob.
+        let pinned = hex::decode("72fa06a07f44524ec8002752250069fef605406ef6790d9c33762e603c19fac6830147c1d44b296873c86670c05f70775f06f53ff75f2c02173d156f47e0eef8a7503bb3dbbf02ff8258503dd61891ed8266dc94fe9291fca3e8d494ede1a9d5a40ce42e460567fd6004792383273b0d8f3d3fcfa8d96d6145d08e78fb1d0a37160e7dcee9a66276aad129efac3c9b4acb99fe19490e554b00dcd8710a3637df220e78e480fef9d0f9d4c64cd4542198fd71a4ee77ef53ce5d3a983225319bf5d10f").unwrap();
+        let params = Generators::<Ed25519>::from_seed(b"legacy");
+        let keypair = KeyPair::<Ed25519>::from_secret(Scalar::from(1234)).unwrap();
+        let x = hash_to_curve::<Ed25519>(&Dst::new(b"test").unwrap(), b"legacy");
+        assert_eq!(Ok(()), VRFOutput::verify_canonical(&pinned, keypair.public_key(), &x, &params));
+        let output = VRFOutput::eval_deterministic(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x).unwrap();
+        assert_eq!(pinned, output.to_proof_bytes());
+        assert_eq!(PROOF_VERSION, pinned[32]);
+
+        let mut unknown = pinned;
+        unknown[32] = 0x84;
+        assert_eq!(Err(VrfError::UnsupportedVersion), VRFOutput::verify_canonical(&unknown, keypair.public_key(), &x, &params));
+    }
+
+    #[test]
+    fn test_inversion_proof_bytes_wrong_curve() {
+        let g = Point::<Ed25519>::generator().to_point();
+        let h = &g * &Scalar::<Ed25519>::random();
+        let params = Generators::<Ed25519>::from_seed(b"bytes");
+        let gamma = Scalar::<Ed25519>::random();
+        let (delta, theta) = (&g * &gamma, &h * &gamma.invert().unwrap());
+        let bytes = InversionProof::prove(&g, &h, &params.g_tilde, &params.h_tilde, &gamma, &delta, &theta).to_bytes();
+        assert_eq!(Err(VrfError::WrongCurve), InversionProof::<Secp256k1>::from_bytes(&bytes));
+    }
+
+    #[test]
+    fn test_ed25519_inversion_proof_bytes() {
+        test_generic_inversion_proof_bytes::<Ed25519>()
+    }
+
+    fn test_generic_display<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"display");
+        let keypair = KeyPair::<C>::generate();
         let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+        let displayed = output.to_string();
+        assert_eq!(displayed.to_lowercase(), displayed);
+        assert_eq!(Ok(output.clone()), VRFOutput::from_proof_bytes(&hex::decode(displayed).unwrap(), &x));
+        assert_eq!(Ok(output.r.clone()), InversionProof::from_bytes(&hex::decode(output.r.to_string()).unwrap()));
+    }
 
-        let g_tilde = Point::<C>::generator() * &Scalar::<C>::random();
-        let h_tilde = Point::<C>::generator() * &Scalar::<C>::random();
+    #[test]
+    fn test_ed25519_display() {
+        test_generic_display::<Ed25519>()
+    }
 
-        let output = VRFOutput::eval(&g_tilde, &h_tilde, &vk, &sk, &x);
-        assert_eq!(true, output.verify(&g_tilde, &h_tilde, &vk, &x));
+    fn test_generic_hex<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"hex");
+        let keypair = KeyPair::<C>::generate();
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+        let hex = output.to_hex();
+        assert_eq!(Ok(output.clone()), VRFOutput::from_hex(&hex, &x));
+        assert_eq!(Ok(output.r.clone()), InversionProof::from_hex(&output.r.to_hex()));
+        assert_eq!(Err(VrfError::InvalidEncoding), VRFOutput::from_hex(&hex[1..], &x));
+        assert_eq!(Err(VrfError::InvalidEncoding), VRFOutput::from_hex(&format!("{}zz", &hex[2..]), &x));
+        assert_eq!(Err(VrfError::InvalidEncoding), InversionProof::<C>::from_hex("0"));
+        assert_eq!(Err(VrfError::InvalidLength), InversionProof::<C>::from_hex(""));
+    }
+
+    #[test]
+    fn test_ed25519_hex() {
+        test_generic_hex::<Ed25519>()
+    }
+
+    fn test_generic_eval_with_rng<C: Curve>() {
+        use rand_chacha::ChaCha20Rng;
+
+        let params = Generators::<C>::from_seed(b"rng");
+        let x = Point::<C>::generator() * &Scalar::<C>::from(7);
+        let eval = |seed: [u8; 32]| {
+            let rng = &mut ChaCha20Rng::from_seed(seed);
+            let keypair = KeyPair::<C>::generate_with_rng(rng);
+            let output = VRFOutput::eval_with_rng(rng, &params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x).unwrap();
+            (keypair.public_key().clone(), output)
+        };
+        let (vk, output) = eval([1; 32]);
+        assert!(output.verify(&params.g_tilde, &params.h_tilde, &vk, &x));
+        assert_eq!((vk.clone(), output.clone()), eval([1; 32]));
+        assert_ne!(vk, eval([2; 32]).0);
+
+        let keypair = KeyPair::<C>::generate_with_rng(&mut ChaCha20Rng::from_seed([1; 32]));
+        let (vk, sk) = (keypair.public_key(), keypair.secret_key());
+        let rng = || ChaCha20Rng::from_seed([3; 32]);
+        let base = VRFOutput::hash_point(vk, &x);
+        let prehashed = VRFOutput::eval_prehashed_with_rng(&mut rng(), &params.g_tilde, &params.h_tilde, vk, sk, &x, &base).unwrap();
+        assert!(prehashed.verify_prehashed(&params.g_tilde, &params.h_tilde, vk, &x, &base));
+        assert_eq!(prehashed, VRFOutput::eval_prehashed_with_rng(&mut rng(), &params.g_tilde, &params.h_tilde, vk, sk, &x, &base).unwrap());
+        let bound = VRFOutput::eval_with_associated_data_with_rng(&mut rng(), &params.g_tilde, &params.h_tilde, vk, sk, &x, b"data").unwrap();
+        assert!(bound.verify_with_associated_data(&params.g_tilde, &params.h_tilde, vk, &x, b"data"));
+        assert_eq!(bound, VRFOutput::eval_with_associated_data_with_rng(&mut rng(), &params.g_tilde, &params.h_tilde, vk, sk, &x, b"data").unwrap());
+    }
+
+    #[test]
+    fn test_ed25519_eval_with_rng() {
+        test_generic_eval_with_rng::<Ed25519>()
+    }
+
+    fn test_generic_expand<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"expand");
+        let keypair = KeyPair::<C>::generate();
+        let output = VRFOutput::eval_bytes(&keypair, &params, b"msg");
+        let values = output.expand(8);
+        assert_eq!(values, VRFOutput::eval_bytes(&keypair, &params, b"msg").expand(8));
+        assert!(values.iter().enumerate().all(|(i, a)| values[i + 1..].iter().all(|b| a != b)));
+        assert_ne!(values, VRFOutput::eval_bytes(&keypair, &params, b"other").expand(8));
+    }
+
+    #[test]
+    fn test_ed25519_expand() {
+        test_generic_expand::<Ed25519>()
+    }
+
+    fn test_generic_batch_verify<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"batch");
+        let proofs: Vec<_> = (0..3).map(|_| {
+            let keypair = KeyPair::<C>::generate();
+            let x = Point::<C>::generator() * &Scalar::<C>::random();
+            let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+            (output, keypair.public_key().clone(), x)
+        }).collect();
+        let batch: Vec<_> = proofs.iter().map(|(output, vk, x)| (output, vk, x)).collect();
+        assert_eq!(Ok(true), BatchVerify::new(&params).verify(&batch));
+
+        let other_x = Point::<C>::generator() * &Scalar::<C>::random();
+        let mut tampered = batch.clone();
+        tampered[1].2 = &other_x;
+        assert_eq!(Ok(false), BatchVerify::new(&params).verify(&tampered));
+
+        assert_eq!(Ok(true), BatchVerify::new(&params).max_batch_size(3).verify(&batch));
+        assert_eq!(Err(VrfError::BatchTooLarge), BatchVerify::new(&params).max_batch_size(2).verify(&batch));
+
+        let oversized = vec![batch[0]; DEFAULT_MAX_BATCH_SIZE + 1];
+        let mut ops = crate::metrics::OpCounts::default();
+        assert_eq!(Err(VrfError::BatchTooLarge), BatchVerify::new(&params).verify_counted(&oversized, &mut ops));
+        assert_eq!(crate::metrics::OpCounts::default(), ops);
+    }
+
+    #[test]
+    fn test_ed25519_batch_verify() {
+        test_generic_batch_verify::<Ed25519>()
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_ed25519_batch_verify_parallel() {
+        let params = Generators::<Ed25519>::from_seed(b"batch");
+        let proofs: Vec<_> = (0..8).map(|_| {
+            let keypair = KeyPair::<Ed25519>::generate();
+            let x = Point::<Ed25519>::generator() * &Scalar::<Ed25519>::random();
+            let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+            (output, keypair.public_key().clone(), x)
+        }).collect();
+        let other_x = Point::<Ed25519>::generator() * &Scalar::<Ed25519>::random();
+        let batch: Vec<_> = proofs.iter().map(|(output, vk, x)| (output, vk, x)).collect();
+        let mut tampered = batch.clone();
+        tampered[5].2 = &other_x;
+
+        let verifier = BatchVerify::new(&params);
+        for batch in [&batch, &tampered] {
+            assert_eq!(verifier.verify(batch), verifier.verify_parallel(batch));
+        }
+        assert_eq!(Ok(true), verifier.verify_parallel(&batch));
+        assert_eq!(Ok(false), verifier.verify_parallel(&tampered));
+        assert_eq!(Err(VrfError::BatchTooLarge), BatchVerify::new(&params).max_batch_size(7).verify_parallel(&batch));
+    }
+
+    fn test_generic_eval_split<C: Curve>() {
+        let params = Generators::<C>::from_seed(b"split");
+        let keypair = KeyPair::<C>::generate();
+        let (vk, sk) = (keypair.public_key(), keypair.secret_key());
+        let x = Point::<C>::generator() * &Scalar::<C>::random();
+        let (output, proof) = VRFOutput::eval_split(&params.g_tilde, &params.h_tilde, vk, sk, &x);
+        assert!(VRFOutput::verify_split(&output, &proof, &params.g_tilde, &params.h_tilde, vk, &x));
+        assert_eq!(VRFOutput::eval(&params.g_tilde, &params.h_tilde, vk, sk, &x).output_value(), output.value());
+
+        let other_x = Point::<C>::generator() * &Scalar::<C>::random();
+        let (other_output, other_proof) = VRFOutput::eval_split(&params.g_tilde, &params.h_tilde, vk, sk, &other_x);
+        assert!(!VRFOutput::verify_split(&output, &other_proof, &params.g_tilde, &params.h_tilde, vk, &x));
+        assert!(!VRFOutput::verify_split(&other_output, &proof, &params.g_tilde, &params.h_tilde, vk, &x));
+    }
+
+    #[test]
+    fn test_ed25519_eval_split() {
+        test_generic_eval_split::<Ed25519>()
+    }
+
+    fn test_generic_transcript<C: Curve>() {
+        let points: Vec<_> = (0..10).map(|_| Point::<C>::generator() * &Scalar::<C>::random()).collect();
+        let [g, h, g_tilde, h_tilde, delta, theta, s1, s2, t0, t1] = <&[_; 10]>::try_from(points.as_slice()).unwrap();
+        let transcript = InversionProof::transcript(g, h, g_tilde, h_tilde, delta, theta, s1, s2, t0, t1);
+        assert_eq!(10 * (8 + g.to_bytes(true).len()), transcript.len());
+        assert_eq!(
+            hash_to_scalar::<C>(&transcript),
+            InversionProof::challenge(g, h, g_tilde, h_tilde, delta, theta, s1, s2, t0, t1)
+        );
+    }
+
+    #[test]
+    fn test_ed25519_transcript() {
+        test_generic_transcript::<Ed25519>()
+    }
+
+    fn test_generic_invert_ct<C: Curve>() {
+        for _ in 0..8 {
+            let s = Scalar::<C>::random();
+            let inv = invert_ct(&s).unwrap();
+            assert_eq!(s.invert(), Some(inv.clone()));
+            assert_eq!(Scalar::<C>::from(1), &s * &inv);
+        }
+        assert_eq!(Some(Scalar::<C>::from(1)), invert_ct(&Scalar::<C>::from(1)));
+        assert_eq!(None, invert_ct(&Scalar::<C>::zero()));
+    }
+
+    #[test]
+    fn test_ed25519_invert_ct() {
+        test_generic_invert_ct::<Ed25519>()
+    }
+
+    #[test]
+    fn test_secp256k1_invert_ct() {
+        test_generic_invert_ct::<Secp256k1>()
+    }
+
+    #[test]
+    fn test_secp256k1_eth_bytes() {
+        let params = Generators::<Secp256k1>::from_seed(b"eth");
+        let keypair = KeyPair::<Secp256k1>::from_secret(Scalar::from(0x1234)).unwrap();
+        let output = VRFOutput::eval_bytes(&keypair, &params, b"eth message");
+        let bytes = output.to_eth_bytes();
+        assert_eq!(322, bytes.len());
+        // `u` and `y` are fixed by the key and message; the proof is randomized.
+        assert_eq!("0498a083f4eb0b96e9ad316a6fc3bb76ca70e6d574553fae80485d9492b6537f9e0b54fe8d83c35e502e8ded1771941cedd5ebac038a9baadb5952dadda91e34d6", hex::encode(&bytes[..65]));
+        assert_eq!("60a84e1d3c450d1bac0dea221540725a60de8debccf33d310faa333adbc642cdcd1725e1fcbabe21ccfa2c79551b9beca29c0823f3305f99b12fdfd770f6eeb7", hex::encode(&bytes[65..129]));
+        assert_eq!(0x04, bytes[129]);
+        assert_eq!(output.r.t1_point, Point::<Secp256k1>::from_bytes(&bytes[129..194]).unwrap());
+        assert_eq!(output.r.x, Scalar::<Secp256k1>::from_bytes(&bytes[290..]).unwrap());
+    }
+
+    #[test]
+    fn test_ed25519_input_table() {
+        test_generic_input_table::<Ed25519>()
+    }
+
+    #[test]
+    fn test_ed25519_keypair() {
+        test_generic_keypair::<Ed25519>()
+    }
+
+    #[test]
+    fn test_ed25519_non_reduced_secret() {
+        let order = Scalar::<Ed25519>::group_order();
+        let mut bytes = (order + BigInt::from(5)).to_bytes();
+        bytes.reverse();
+        let sk = Scalar::<Ed25519>::from_bytes(&bytes).unwrap();
+        assert_ne!(Scalar::<Ed25519>::from(5), sk);
+
+        let keypair = KeyPair::from_secret(sk.clone()).unwrap();
+        assert_eq!(Scalar::<Ed25519>::from(5), *keypair.secret_key());
+        assert_eq!(Point::<Ed25519>::generator() * &sk, *keypair.public_key());
+
+        let params = Generators::<Ed25519>::from_seed(b"reduce");
+        let x = Point::<Ed25519>::generator() * &Scalar::<Ed25519>::random();
+        let output = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), &sk, &x);
+        assert!(output.verify(&params.g_tilde, &params.h_tilde, keypair.public_key(), &x));
+        let reduced = VRFOutput::eval(&params.g_tilde, &params.h_tilde, keypair.public_key(), keypair.secret_key(), &x);
+        assert_eq!(reduced.y, output.y);
+
+        let mut bytes = order.to_bytes();
+        bytes.reverse();
+        let zero = Scalar::<Ed25519>::from_bytes(&bytes).unwrap();
+        assert!(matches!(KeyPair::from_secret(zero), Err(VrfError::ZeroSecretKey)));
+    }
+
+    #[test]
+    fn test_dst() {
+        let dst = Dst::new(b"QUUX-V01-CS02-with-expander-SHA512-256").unwrap();
+        assert_eq!(b"QUUX-V01-CS02-with-expander-SHA512-256", dst.as_bytes());
+        assert_eq!(
+            "6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba",
+            hex::encode(expand_message_xmd::<32>(b"", &dst))
+        );
+        assert_eq!(
+            hash_to_curve::<Ed25519>(&dst, b"msg"),
+            hash_to_curve::<Ed25519>(&Dst::new(dst.as_bytes()).unwrap(), b"msg")
+        );
+    }
+
+    #[test]
+    fn test_wide_hash_len() {
+        assert_eq!(64, wide_hash_len(Scalar::<Ed25519>::group_order()));
+        assert_eq!(64, wide_hash_len(&BigInt::from(251)));
+        assert_eq!(80, wide_hash_len(&(BigInt::from(1) << 511)));
+        let wide = reduce_wide(sha2::Sha512::new().chain(b"wide"), &(BigInt::from(1) << 511));
+        assert!(wide.bit_length() > 500);
+    }
+
+    #[test]
+    fn test_reduce_wide_small_order() {
+        let order = BigInt::from(251);
+        let mut counts = [0usize; 251];
+        for i in 0u32..25100 {
+            let r = reduce_wide(sha2::Sha512::new().chain(i.to_be_bytes()), &order);
+            counts[r.to_bytes().first().map_or(0, |&b| b as usize)] += 1;
+        }
+        // Expect 100 per bucket; the chi-squared statistic for 250 degrees of
+        // freedom stays well under 350 for a uniform distribution.
+        let chi2: f64 = counts.iter().map(|&c| (c as f64 - 100.0).powi(2) / 100.0).sum();
+        assert!(chi2 < 350.0, "chi2 = {}", chi2);
+    }
+
+    #[test]
+    fn test_empty_dst() {
+        assert_eq!(None, Dst::new(b""));
+    }
+
+    #[test]
+    fn test_oversize_dst() {
+        let tag = [0x61u8; 256];
+        let dst = Dst::new(&tag).unwrap();
+        let expected = sha2::Sha512::new().chain(b"H2C-OVERSIZE-DST-").chain(tag).finalize();
+        assert_eq!(expected.as_slice(), dst.as_bytes());
+        assert_eq!(255, Dst::new(&tag[..255]).unwrap().as_bytes().len());
+        assert_ne!(
+            hash_to_curve::<Ed25519>(&dst, b"msg"),
+            hash_to_curve::<Ed25519>(&Dst::new(&tag[..255]).unwrap(), b"msg")
+        );
     }
 
     #[test]
@@ -188,11 +3060,46 @@ mod tests {
         test_generic_inversion_proof::<Ed25519>()
     }
 
+    #[test]
+    fn test_ed25519_inversion_proof_custom_h() {
+        test_generic_inversion_proof_custom_h::<Ed25519>()
+    }
+
+    #[test]
+    fn test_ed25519_inversion_proof_tampered() {
+        test_generic_inversion_proof_tampered::<Ed25519>()
+    }
+
     #[test]
     fn test_ed25519_vrf() {
         test_generic_vrf::<Ed25519>()
     }
 
+    #[test]
+    fn test_secp256k1_vrf() {
+        test_generic_vrf::<Secp256k1>()
+    }
+
+    #[test]
+    fn test_secp256r1_vrf() {
+        test_generic_vrf::<Secp256r1>()
+    }
+
+    #[test]
+    fn test_ed25519_generators_from_seed() {
+        test_generic_generators_from_seed::<Ed25519>()
+    }
+
+    #[test]
+    fn test_ed25519_clone_eq_debug() {
+        test_generic_clone_eq_debug::<Ed25519>()
+    }
+
+    #[test]
+    fn test_ed25519_verify_aggregate_key() {
+        test_generic_verify_aggregate_key::<Ed25519>()
+    }
+
     // fn bench_generic_vrf<C: Curve>(repetition: usize) -> (Duration, Duration){
     //     let sk = Scalar::<C>::random();
     //     let vk = Point::<C>::generator() * &sk;
@@ -0,0 +1,50 @@
+use std::cmp::Ordering;
+
+/// Picks the leader among `(public key bytes, VRF output)` candidates: the
+/// smallest output wins. Equal outputs are broken by comparing the public key
+/// bytes lexicographically, the smaller key winning, so every node selects the
+/// same leader regardless of the order candidates were received in.
+pub fn select_leader<K: AsRef<[u8]>, O: Ord>(candidates: &[(K, O)]) -> Option<usize> {
+    (0..candidates.len()).min_by(|&i, &j| compare(&candidates[i], &candidates[j]))
+}
+
+fn compare<K: AsRef<[u8]>, O: Ord>(a: &(K, O), b: &(K, O)) -> Ordering {
+    a.1.cmp(&b.1).then_with(|| a.0.as_ref().cmp(b.0.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::select_leader;
+
+    #[test]
+    fn test_smallest_output_wins() {
+        let candidates = [
+            (vec![1u8; 32], vec![9u8; 64]),
+            (vec![2u8; 32], vec![3u8; 64]),
+            (vec![3u8; 32], vec![5u8; 64]),
+        ];
+        assert_eq!(Some(1), select_leader(&candidates));
+    }
+
+    #[test]
+    fn test_tie_broken_by_smaller_key() {
+        let candidates = [
+            (vec![7u8; 32], vec![4u8; 64]),
+            (vec![5u8; 32], vec![4u8; 64]),
+            (vec![6u8; 32], vec![8u8; 64]),
+        ];
+        assert_eq!(Some(1), select_leader(&candidates));
+
+        let reversed = [
+            candidates[2].clone(),
+            candidates[1].clone(),
+            candidates[0].clone(),
+        ];
+        assert_eq!(Some(1), select_leader(&reversed));
+    }
+
+    #[test]
+    fn test_no_candidates() {
+        assert_eq!(None, select_leader::<Vec<u8>, Vec<u8>>(&[]));
+    }
+}
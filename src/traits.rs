@@ -0,0 +1,47 @@
+/// Common interface over the VRF schemes in this crate, so callers can be
+/// generic over which one they use.
+pub trait Vrf {
+    type PublicKey;
+    type SecretKey;
+    type Output;
+    type Proof;
+
+    fn keygen(&self) -> (Self::SecretKey, Self::PublicKey);
+
+    fn eval(&self, sk: &Self::SecretKey, msg: &[u8]) -> (Self::Output, Self::Proof);
+
+    fn verify(&self, vk: &Self::PublicKey, msg: &[u8], proof: &Self::Proof) -> bool;
+}
+
+#[cfg(all(test, not(feature = "no_panic")))]
+mod tests {
+    use curv::elliptic::curves::Ed25519;
+
+    use super::Vrf;
+    use crate::{ec_vrf::EcVrf, rka_vrf::{Generators, RkaVrf}};
+
+    fn test_generic_scheme<V: Vrf>(vrf: &V)
+    where
+        V::Output: PartialEq + std::fmt::Debug
+    {
+        let (sk, vk) = vrf.keygen();
+        let (output, proof) = vrf.eval(&sk, b"message");
+        let (again, _) = vrf.eval(&sk, b"message");
+        assert_eq!(output, again);
+        assert!(vrf.verify(&vk, b"message", &proof));
+        assert!(!vrf.verify(&vk, b"other message", &proof));
+
+        let (_, other_vk) = vrf.keygen();
+        assert!(!vrf.verify(&other_vk, b"message", &proof));
+    }
+
+    #[test]
+    fn test_ec_vrf_scheme() {
+        test_generic_scheme(&EcVrf)
+    }
+
+    #[test]
+    fn test_ed25519_rka_vrf_scheme() {
+        test_generic_scheme(&RkaVrf::new(Generators::<Ed25519>::from_seed(b"scheme")))
+    }
+}
@@ -0,0 +1,70 @@
+use sha2::{Digest, Sha512};
+
+/// Where a `TranscriptHasher` writes its framed elements.
+pub trait TranscriptSink {
+    fn absorb(&mut self, bytes: &[u8]);
+}
+
+impl TranscriptSink for Sha512 {
+    fn absorb(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+}
+
+/// Keeps the framed bytes themselves, e.g. for re-deriving a hash elsewhere.
+impl TranscriptSink for Vec<u8> {
+    fn absorb(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+}
+
+/// Sha512 over a sequence of elements, each prefixed with its length as a
+/// little-endian `u64`, so element boundaries stay unambiguous even once
+/// variable-length data such as contexts or messages is absorbed.
+#[derive(Clone, Default)]
+pub struct TranscriptHasher<S = Sha512>(S);
+
+impl TranscriptHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn finalize(self) -> [u8; 64] {
+        self.0.finalize().as_slice().try_into().unwrap()
+    }
+}
+
+impl <S: TranscriptSink> TranscriptHasher<S> {
+    /// Appends to whatever `sink` already holds.
+    pub fn with_sink(sink: S) -> Self {
+        Self(sink)
+    }
+
+    pub fn append(mut self, element: &[u8]) -> Self {
+        self.0.absorb(&(element.len() as u64).to_le_bytes());
+        self.0.absorb(element);
+        self
+    }
+
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Digest, Sha512};
+
+    use super::TranscriptHasher;
+
+    #[test]
+    fn test_boundaries() {
+        let a_bc = TranscriptHasher::new().append(b"a").append(b"bc").finalize();
+        let ab_c = TranscriptHasher::new().append(b"ab").append(b"c").finalize();
+        assert_ne!(a_bc, ab_c);
+        assert_eq!("440fa827fe934551e827b5ede86336005cc35af317d1cb4c69841a558608f8756b3d7ba1fb887b7b0fa2c012c000942e7f67729ac4a40aecfd08c35a3efa27e3", hex::encode(a_bc));
+
+        let bytes = TranscriptHasher::with_sink(Vec::new()).append(b"a").append(b"bc").into_inner();
+        assert_eq!(a_bc, Sha512::digest(&bytes)[..]);
+    }
+}
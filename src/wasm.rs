@@ -0,0 +1,54 @@
+//! Byte-oriented `ec_vrf` entry points for JavaScript callers. On
+//! `wasm32-unknown-unknown` they are exported through wasm-bindgen and
+//! randomness comes from the browser via getrandom's `wasm-bindgen` backend.
+//! Verification draws no randomness at all.
+
+use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, scalar::Scalar};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use crate::{ec_vrf::{PublicKey, VRFOutput}, error::VrfError};
+
+/// Evaluates the VRF on `msg` with the 32-byte canonical secret scalar `sk`,
+/// returning the 96-byte proof.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn ec_vrf_eval(sk: &[u8], msg: &[u8]) -> Result<Vec<u8>, String> {
+    let sk: [u8; 32] = sk.try_into().map_err(|_| VrfError::InvalidLength.to_string())?;
+    let sk = Scalar::from_canonical_bytes(sk).ok_or_else(|| VrfError::NonCanonicalScalar.to_string())?;
+    if sk == Scalar::zero() {
+        return Err(VrfError::ZeroSecretKey.to_string());
+    }
+    let vk = ED25519_BASEPOINT_POINT * sk;
+    Ok(VRFOutput::eval_message(&vk, &sk, msg).to_proof_bytes().to_vec())
+}
+
+/// Verifies a 96-byte proof for `msg` under the 32-byte compressed `vk`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn ec_vrf_verify(vk: &[u8], msg: &[u8], proof: &[u8]) -> bool {
+    let (Ok(vk), Ok(proof)) = (<&[u8; 32]>::try_from(vk), <&[u8; 96]>::try_from(proof)) else {
+        return false;
+    };
+    match (PublicKey::from_bytes(vk), VRFOutput::from_proof_bytes(proof)) {
+        (Ok(vk), Ok(proof)) => proof.verify_message(vk.as_point(), msg),
+        _ => false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, scalar::Scalar};
+
+    use super::{ec_vrf_eval, ec_vrf_verify};
+
+    #[test]
+    fn test_byte_wrappers() {
+        let sk = Scalar::random(&mut rand::thread_rng());
+        let vk = (ED25519_BASEPOINT_POINT * sk).compress().to_bytes();
+        let proof = ec_vrf_eval(sk.as_bytes(), b"message").unwrap();
+        assert!(ec_vrf_verify(&vk, b"message", &proof));
+        assert!(!ec_vrf_verify(&vk, b"other", &proof));
+        assert!(!ec_vrf_verify(&vk[..31], b"message", &proof));
+        assert!(ec_vrf_eval(&[0u8; 32], b"message").is_err());
+        assert!(ec_vrf_eval(&[0xffu8; 32], b"message").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+// Drives the `vrf` binary end to end: keygen, eval, then verify, checking
+// the JSON it prints and that a failed verification exits nonzero.
+use std::process::{Command, Output};
+
+fn vrf(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vrf")).args(args).output().unwrap()
+}
+
+fn json(output: &Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn field(value: &serde_json::Value, name: &str) -> String {
+    value[name].as_str().unwrap().to_string()
+}
+
+fn test_scheme(scheme: &str) {
+    let msg = hex::encode(b"message");
+    let keys = json(&vrf(&["keygen", "--scheme", scheme]));
+    let eval = json(&vrf(&["eval", "--scheme", scheme, "--sk", &field(&keys, "sk"), "--msg", &msg]));
+    assert_eq!(field(&keys, "vk"), field(&eval, "vk"));
+    let (vk, proof) = (field(&eval, "vk"), field(&eval, "proof"));
+
+    let valid = vrf(&["verify", "--scheme", scheme, "--vk", &vk, "--msg", &msg, "--proof", &proof]);
+    assert_eq!(Some(0), valid.status.code());
+    assert_eq!(serde_json::json!({ "valid": true }), json(&valid));
+
+    let other_msg = hex::encode(b"another message");
+    let invalid = vrf(&["verify", "--scheme", scheme, "--vk", &vk, "--msg", &other_msg, "--proof", &proof]);
+    assert_eq!(Some(1), invalid.status.code());
+    assert_eq!(serde_json::json!({ "valid": false }), json(&invalid));
+
+    let malformed = vrf(&["verify", "--scheme", scheme, "--vk", &vk, "--msg", &msg, "--proof", "zz"]);
+    assert_eq!(Some(2), malformed.status.code());
+}
+
+#[test]
+fn test_ec_vrf() {
+    test_scheme("ec")
+}
+
+#[test]
+fn test_rka_vrf() {
+    test_scheme("rka")
+}
+
+#[test]
+fn test_usage_error() {
+    assert_eq!(Some(2), vrf(&[]).status.code());
+    assert_eq!(Some(2), vrf(&["eval", "--scheme", "bls"]).status.code());
+}
@@ -0,0 +1,124 @@
+// Shared battery run against every scheme behind `traits::Vrf`. A new scheme
+// gets the full coverage by implementing `Scheme` and adding one `#[test]`.
+// `RkaVrf` evaluates through the panicking `eval_bytes`, so its half is
+// compiled out under `no_panic`.
+#[cfg(not(feature = "no_panic"))]
+use curv::elliptic::curves::{Curve, Ed25519, Secp256k1};
+#[cfg(not(feature = "no_panic"))]
+use vrf::rka_vrf::{self, Generators, RkaVrf, VrfInput};
+use vrf::{
+    ec_vrf::{self, EcVrf},
+    error::VrfError,
+    traits::Vrf
+};
+
+trait Scheme: Vrf {
+    fn proof_to_bytes(&self, proof: &Self::Proof) -> Vec<u8>;
+
+    /// `msg` is needed by schemes whose encoding leaves out parts recomputed
+    /// from the input.
+    fn proof_from_bytes(&self, bytes: &[u8], msg: &[u8]) -> Result<Self::Proof, VrfError>;
+}
+
+impl Scheme for EcVrf {
+    fn proof_to_bytes(&self, proof: &ec_vrf::VRFOutput) -> Vec<u8> {
+        proof.to_proof_bytes().to_vec()
+    }
+
+    fn proof_from_bytes(&self, bytes: &[u8], _msg: &[u8]) -> Result<ec_vrf::VRFOutput, VrfError> {
+        ec_vrf::VRFOutput::from_proof_slice(bytes)
+    }
+}
+
+#[cfg(not(feature = "no_panic"))]
+impl <C: Curve> Scheme for RkaVrf<C> {
+    fn proof_to_bytes(&self, proof: &rka_vrf::VRFOutput<C>) -> Vec<u8> {
+        proof.to_proof_bytes()
+    }
+
+    fn proof_from_bytes(&self, bytes: &[u8], msg: &[u8]) -> Result<rka_vrf::VRFOutput<C>, VrfError> {
+        rka_vrf::VRFOutput::from_proof_bytes(bytes, VrfInput::from_message(msg).as_point())
+    }
+}
+
+struct TestVector<V: Vrf> {
+    msg: Vec<u8>,
+    vk: V::PublicKey,
+    output: V::Output,
+    proof: V::Proof,
+    proof_bytes: Vec<u8>
+}
+
+impl <V: Scheme> TestVector<V> {
+    fn generate(vrf: &V, msg: &[u8]) -> Self {
+        let (sk, vk) = vrf.keygen();
+        let (output, proof) = vrf.eval(&sk, msg);
+        let proof_bytes = vrf.proof_to_bytes(&proof);
+        Self { msg: msg.to_vec(), vk, output, proof, proof_bytes }
+    }
+}
+
+fn test_valid<V: Scheme>(vrf: &V, vector: &TestVector<V>) {
+    assert!(vrf.verify(&vector.vk, &vector.msg, &vector.proof));
+    assert!(!vrf.verify(&vector.vk, b"another message", &vector.proof));
+    let (_, other_vk) = vrf.keygen();
+    assert!(!vrf.verify(&other_vk, &vector.msg, &vector.proof));
+}
+
+fn test_deterministic_output<V: Scheme>(vrf: &V, vector: &TestVector<V>)
+where
+    V::Output: PartialEq + std::fmt::Debug
+{
+    let (sk, _) = vrf.keygen();
+    assert_eq!(vrf.eval(&sk, &vector.msg).0, vrf.eval(&sk, &vector.msg).0);
+    assert_ne!(vrf.eval(&sk, &vector.msg).0, vrf.eval(&sk, b"another message").0);
+    assert_ne!(vector.output, vrf.eval(&sk, &vector.msg).0);
+}
+
+fn test_serialization<V: Scheme>(vrf: &V, vector: &TestVector<V>) {
+    let decoded = vrf.proof_from_bytes(&vector.proof_bytes, &vector.msg).unwrap();
+    assert_eq!(vector.proof_bytes, vrf.proof_to_bytes(&decoded));
+    assert!(vrf.verify(&vector.vk, &vector.msg, &decoded));
+    let truncated = &vector.proof_bytes[..vector.proof_bytes.len() - 1];
+    assert_eq!(Err(VrfError::InvalidLength), vrf.proof_from_bytes(truncated, &vector.msg).map(|_| ()));
+}
+
+// Flipping any bit must make the proof fail to decode or fail to verify.
+fn test_tampered<V: Scheme>(vrf: &V, vector: &TestVector<V>) {
+    let len = vector.proof_bytes.len();
+    for i in [0, 1, len / 3, len / 2, 2 * len / 3, len - 1] {
+        let mut bytes = vector.proof_bytes.clone();
+        bytes[i] ^= 0x01;
+        if let Ok(proof) = vrf.proof_from_bytes(&bytes, &vector.msg) {
+            assert!(!vrf.verify(&vector.vk, &vector.msg, &proof), "byte {} tampered", i);
+        }
+    }
+}
+
+fn run_all<V: Scheme>(vrf: &V)
+where
+    V::Output: PartialEq + std::fmt::Debug
+{
+    let vector = TestVector::generate(vrf, b"message");
+    test_valid(vrf, &vector);
+    test_deterministic_output(vrf, &vector);
+    test_serialization(vrf, &vector);
+    test_tampered(vrf, &vector);
+}
+
+#[test]
+fn test_ec_vrf() {
+    run_all(&EcVrf)
+}
+
+#[cfg(not(feature = "no_panic"))]
+#[test]
+fn test_ed25519_rka_vrf() {
+    run_all(&RkaVrf::new(Generators::<Ed25519>::from_seed(b"harness")))
+}
+
+#[cfg(not(feature = "no_panic"))]
+#[test]
+fn test_secp256k1_rka_vrf() {
+    run_all(&RkaVrf::new(Generators::<Secp256k1>::from_seed(b"harness")))
+}
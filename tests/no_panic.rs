@@ -0,0 +1,44 @@
+// Built only with `--features no_panic`; the fallible APIs must cover what
+// the removed conveniences did.
+#![cfg(feature = "no_panic")]
+use curv::elliptic::curves::{Ed25519, Point, Scalar};
+use vrf::{error::VrfError, rka_vrf::{Generators, InversionProof, KeyPair, VRFOutput}};
+
+#[test]
+fn test_fallible_eval() {
+    let params = Generators::<Ed25519>::from_seed(b"no_panic");
+    let keypair = KeyPair::<Ed25519>::generate();
+    let (vk, sk) = (keypair.public_key(), keypair.secret_key());
+    let x = Point::<Ed25519>::generator() * &Scalar::<Ed25519>::random();
+
+    let output = VRFOutput::try_eval(&params.g_tilde, &params.h_tilde, vk, sk, &x).unwrap();
+    assert!(output.verify(&params.g_tilde, &params.h_tilde, vk, &x));
+    let base = VRFOutput::hash_point(vk, &x);
+    let prehashed = VRFOutput::try_eval_prehashed(&params.g_tilde, &params.h_tilde, vk, sk, &x, &base).unwrap();
+    assert_eq!(output.output_value(), prehashed.output_value());
+
+    let zero = Scalar::<Ed25519>::zero();
+    assert_eq!(
+        Some(VrfError::ZeroSecretKey),
+        VRFOutput::try_eval_prehashed(&params.g_tilde, &params.h_tilde, vk, &zero, &x, &base).err()
+    );
+    let identity = Point::<Ed25519>::zero();
+    assert!(VRFOutput::try_eval(&params.g_tilde, &params.h_tilde, vk, sk, &identity).is_err());
+}
+
+#[test]
+fn test_fallible_zero_key() {
+    let params = Generators::<Ed25519>::from_seed(b"no_panic");
+    let keypair = KeyPair::<Ed25519>::generate();
+    let (vk, sk) = (keypair.public_key(), keypair.secret_key());
+    let x = Point::<Ed25519>::generator() * &Scalar::<Ed25519>::random();
+    let zero = Scalar::<Ed25519>::zero();
+
+    assert!(VRFOutput::try_eval_encrypted(vk, vk, sk, &x).unwrap().verify(vk, vk, &x));
+    assert_eq!(Some(VrfError::ZeroSecretKey), VRFOutput::try_eval_encrypted(vk, vk, &zero, &x).err());
+    let (g, h) = (Point::<Ed25519>::generator().to_point(), vk.clone());
+    assert_eq!(
+        Some(VrfError::ZeroSecretKey),
+        InversionProof::try_prove(&g, &h, &params.g_tilde, &params.h_tilde, &zero, &g, &h).err()
+    );
+}